@@ -0,0 +1,132 @@
+// Parsiranje velikog generiranog računa
+//
+// cargo bench --bench parse [-- <BROJ STAVKI>]
+
+use std::{fmt::Write, time::Instant};
+
+const HEADER: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Invoice xmlns="urn:oasis:names:specification:ubl:schema:xsd:Invoice-2"
+	xmlns:cac="urn:oasis:names:specification:ubl:schema:xsd:CommonAggregateComponents-2"
+	xmlns:cbc="urn:oasis:names:specification:ubl:schema:xsd:CommonBasicComponents-2"
+	xmlns:ext="urn:oasis:names:specification:ubl:schema:xsd:CommonExtensionComponents-2"
+	xmlns:hrextac="urn:mfin.gov.hr:schema:xsd:HRExtensionAggregateComponents-1">
+	<ext:UBLExtensions>
+		<ext:UBLExtension>
+			<ext:ExtensionContent>
+				<hrextac:HRFISK20Data>
+					<hrextac:OperatorCode>OPER1</hrextac:OperatorCode>
+					<hrextac:BusinessPremisesCode>POSL1</hrextac:BusinessPremisesCode>
+				</hrextac:HRFISK20Data>
+			</ext:ExtensionContent>
+		</ext:UBLExtension>
+	</ext:UBLExtensions>
+	<cbc:CustomizationID>urn:cen.eu:en16931:2017#compliant#urn:mingo.hr:2018:1.0</cbc:CustomizationID>
+	<cbc:ProfileID>P1</cbc:ProfileID>
+	<cbc:ID>1-POSL1-1</cbc:ID>
+	<cbc:IssueDate>2025-01-15</cbc:IssueDate>
+	<cbc:InvoiceTypeCode>380</cbc:InvoiceTypeCode>
+	<cbc:DocumentCurrencyCode>EUR</cbc:DocumentCurrencyCode>
+	<cac:AccountingSupplierParty>
+		<cac:Party>
+			<cbc:EndpointID schemeID="9934">94577403194</cbc:EndpointID>
+			<cac:PostalAddress>
+				<cbc:StreetName>Ilica 1</cbc:StreetName>
+				<cbc:CityName>Zagreb</cbc:CityName>
+				<cac:Country><cbc:IdentificationCode>HR</cbc:IdentificationCode></cac:Country>
+			</cac:PostalAddress>
+			<cac:PartyLegalEntity><cbc:RegistrationName>Prodavatelj d.o.o.</cbc:RegistrationName></cac:PartyLegalEntity>
+		</cac:Party>
+	</cac:AccountingSupplierParty>
+	<cac:AccountingCustomerParty>
+		<cac:Party>
+			<cbc:EndpointID schemeID="9934">12345678903</cbc:EndpointID>
+			<cac:PostalAddress>
+				<cbc:CityName>Split</cbc:CityName>
+				<cac:Country><cbc:IdentificationCode>HR</cbc:IdentificationCode></cac:Country>
+			</cac:PostalAddress>
+			<cac:PartyLegalEntity><cbc:RegistrationName>Kupac d.d.</cbc:RegistrationName></cac:PartyLegalEntity>
+		</cac:Party>
+	</cac:AccountingCustomerParty>
+	<cac:LegalMonetaryTotal>
+		<cbc:LineExtensionAmount currencyID="EUR">1000.00</cbc:LineExtensionAmount>
+		<cbc:TaxExclusiveAmount currencyID="EUR">1000.00</cbc:TaxExclusiveAmount>
+		<cbc:TaxInclusiveAmount currencyID="EUR">1250.00</cbc:TaxInclusiveAmount>
+		<cbc:PayableAmount currencyID="EUR">1250.00</cbc:PayableAmount>
+	</cac:LegalMonetaryTotal>
+"#;
+
+fn generate(lines: usize) -> String {
+	let mut xml = String::from(HEADER);
+	for i in 1..=lines {
+		write!(
+			xml,
+			r#"	<cac:InvoiceLine>
+		<cbc:ID>{i}</cbc:ID>
+		<cbc:InvoicedQuantity unitCode="H87">10</cbc:InvoicedQuantity>
+		<cbc:LineExtensionAmount currencyID="EUR">100.00</cbc:LineExtensionAmount>
+		<cac:InvoicePeriod><cbc:StartDate>2025-01-01</cbc:StartDate></cac:InvoicePeriod>
+		<cac:Item>
+			<cbc:Description>Opis artikla {i}</cbc:Description>
+			<cbc:Name>Artikl {i}</cbc:Name>
+			<cac:SellersItemIdentification><cbc:ID>SKU-{i}</cbc:ID></cac:SellersItemIdentification>
+			<cac:CommodityClassification>
+				<cbc:ItemClassificationCode listID="CG">62.01.11</cbc:ItemClassificationCode>
+			</cac:CommodityClassification>
+			<cac:ClassifiedTaxCategory>
+				<cbc:ID>S</cbc:ID>
+				<cbc:Percent>25</cbc:Percent>
+				<cac:TaxScheme><cbc:ID>VAT</cbc:ID></cac:TaxScheme>
+			</cac:ClassifiedTaxCategory>
+		</cac:Item>
+		<cac:Price>
+			<cbc:PriceAmount currencyID="EUR">10.00</cbc:PriceAmount>
+			<cbc:BaseQuantity unitCode="H87">1</cbc:BaseQuantity>
+		</cac:Price>
+	</cac:InvoiceLine>
+"#
+		)
+		.unwrap();
+	}
+	xml.push_str("</Invoice>\n");
+	xml
+}
+
+const RUNS: u32 = 3;
+
+// Najkraće vrijeme od RUNS ponavljanja
+fn best(parse: impl Fn()) -> f64 {
+	let mut best = f64::MAX;
+	for _ in 0..RUNS {
+		let start = Instant::now();
+		parse();
+		best = best.min(start.elapsed().as_secs_f64());
+	}
+	best
+}
+
+fn main() {
+	let lines = std::env::args().skip(1).find_map(|x| x.parse().ok()).unwrap_or(50_000);
+	let xml = generate(lines);
+	let megabytes = xml.len() as f64 / 1_000_000.0;
+
+	let whole = best(|| {
+		let xml_data = eracun_xml_to_pdf::from_str(&xml).unwrap();
+		assert_eq!(xml_data.invoice_lines.len(), lines);
+	});
+	let streaming = best(|| {
+		let stream = eracun_xml_to_pdf::parse_invoice_stream(xml.as_bytes(), &Default::default()).unwrap();
+		assert_eq!(stream.map(Result::unwrap).count(), lines);
+	});
+
+	for (name, seconds) in [("cijeli račun", whole), ("stavku po stavku", streaming)] {
+		println!(
+			"{}, {} stavki, {:.1} MB: {:.3} s ({:.1} MB/s, najbolje od {})",
+			name,
+			lines,
+			megabytes,
+			seconds,
+			megabytes / seconds,
+			RUNS
+		);
+	}
+}
@@ -0,0 +1,132 @@
+// Popisi šifri iz src/codelist/*.tsv postaju enumi s nazivima, vidi src/codelist.rs
+//
+// Varijanta nosi samu šifru, npr. Unit::H87 ili Currency::EUR; šifre koje počinju znamenkom dobivaju prefiks _,
+// npr. InvoiceType::_380, a crtica postaje podvlaka, npr. Vatex::VATEX_EU_79_C.
+
+use std::{env, fmt::Write, fs, path::Path};
+
+// Ime enuma i datoteka, prvi redak datoteke postaje komentar enuma
+const LISTS: &[(&str, &str)] = &[
+	("InvoiceType", "uncl1001.tsv"),
+	("PaymentMeans", "uncl4461.tsv"),
+	("VatCategory", "uncl5305.tsv"),
+	("Unit", "rec20.tsv"),
+	("Currency", "iso4217.tsv"),
+	("Country", "iso3166.tsv"),
+	("Icd", "icd.tsv"),
+	("Eas", "eas.tsv"),
+	("Vatex", "vatex.tsv"),
+];
+
+struct Row {
+	code: String,
+	variant: String,
+	name_hr: String,
+	name_en: String,
+	// Dodatni stupci, npr. broj decimala valute
+	extra: Vec<String>,
+}
+
+fn main() {
+	println!("cargo::rerun-if-changed=src/codelist");
+
+	let mut out = String::new();
+	for (name, file) in LISTS {
+		let path = Path::new("src/codelist").join(file);
+		let text = fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+		let rows = parse(&text).unwrap_or_else(|line| panic!("{}: neispravan redak {:?}", path.display(), line));
+		let comment = text.lines().next().and_then(|x| x.strip_prefix("# ")).unwrap_or(file);
+		generate(&mut out, name, comment, &rows);
+		if *name == "Currency" {
+			minor_units(&mut out, &rows);
+		}
+	}
+
+	let dest = Path::new(&env::var("OUT_DIR").unwrap()).join("codelist.rs");
+	fs::write(dest, out).unwrap();
+}
+
+// Err s retkom koji nema šifru i oba naziva
+fn parse(text: &str) -> Result<Vec<Row>, &str> {
+	text.lines()
+		.filter(|line| !line.starts_with('#') && !line.trim().is_empty())
+		.map(|line| {
+			let mut columns = line.split('\t');
+			let (Some(code), Some(name_hr), Some(name_en)) = (columns.next(), columns.next(), columns.next()) else {
+				return Err(line);
+			};
+			let mut variant: String = code.chars().map(|x| if x == '-' { '_' } else { x }).collect();
+			if variant.starts_with(|x: char| x.is_ascii_digit()) {
+				variant.insert(0, '_');
+			}
+			if !variant.chars().all(|x| x.is_ascii_alphanumeric() || x == '_') {
+				return Err(line);
+			}
+			Ok(Row {
+				code: code.to_owned(),
+				variant,
+				name_hr: name_hr.to_owned(),
+				name_en: name_en.to_owned(),
+				extra: columns.map(str::to_owned).collect(),
+			})
+		})
+		.collect()
+}
+
+fn generate(out: &mut String, name: &str, comment: &str, rows: &[Row]) {
+	writeln!(out, "// {}", comment).unwrap();
+	writeln!(out, "#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]").unwrap();
+	writeln!(out, "#[allow(non_camel_case_types, clippy::upper_case_acronyms)]").unwrap();
+	writeln!(out, "pub enum {} {{", name).unwrap();
+	for row in rows {
+		writeln!(out, "\t{},", row.variant).unwrap();
+	}
+	writeln!(out, "}}\n").unwrap();
+
+	writeln!(out, "impl {} {{", name).unwrap();
+	write!(out, "\tpub const ALL: &'static [Self] = &[").unwrap();
+	for row in rows {
+		write!(out, "Self::{}, ", row.variant).unwrap();
+	}
+	writeln!(out, "];\n").unwrap();
+
+	writeln!(out, "\tpub fn from_code(code: &str) -> Option<Self> {{").unwrap();
+	writeln!(out, "\t\tmatch code.trim() {{").unwrap();
+	for row in rows {
+		writeln!(out, "\t\t\t{:?} => Some(Self::{}),", row.code, row.variant).unwrap();
+	}
+	writeln!(out, "\t\t\t_ => None,\n\t\t}}\n\t}}\n").unwrap();
+
+	method(out, "code", "&'static str", rows, |row| std::format!("{:?}", row.code));
+	method(out, "name_hr", "&'static str", rows, |row| std::format!("{:?}", row.name_hr));
+	method(out, "name_en", "&'static str", rows, |row| std::format!("{:?}", row.name_en));
+	writeln!(out, "}}\n").unwrap();
+
+	writeln!(out, "impl From<{}> for Code {{", name).unwrap();
+	writeln!(out, "\tfn from(x: {}) -> Self {{", name).unwrap();
+	writeln!(out, "\t\tSelf {{ code: x.code(), name_hr: x.name_hr(), name_en: x.name_en() }}").unwrap();
+	writeln!(out, "\t}}\n}}\n").unwrap();
+}
+
+// Broj decimala iz četvrtog stupca iso4217.tsv
+fn minor_units(out: &mut String, rows: &[Row]) {
+	for row in rows {
+		match row.extra.first().map(|x| x.parse::<u32>()) {
+			Some(Ok(_)) => (),
+			_ => panic!("iso4217.tsv: nedostaje broj decimala za {}", row.code),
+		}
+	}
+
+	writeln!(out, "impl Currency {{").unwrap();
+	method(out, "minor_units", "u32", rows, |row| row.extra[0].clone());
+	writeln!(out, "}}\n").unwrap();
+}
+
+fn method(out: &mut String, name: &str, ty: &str, rows: &[Row], value: impl Fn(&Row) -> String) {
+	writeln!(out, "\tpub fn {}(self) -> {} {{", name, ty).unwrap();
+	writeln!(out, "\t\tmatch self {{").unwrap();
+	for row in rows {
+		writeln!(out, "\t\t\tSelf::{} => {},", row.variant, value(row)).unwrap();
+	}
+	writeln!(out, "\t\t}}\n\t}}\n").unwrap();
+}
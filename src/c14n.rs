@@ -0,0 +1,451 @@
+// Kanonikalizacija XML-a za provjeru potpisa
+//
+// Podržani su Exclusive XML Canonicalization 1.0 i Canonical XML 1.0, oba bez komentara. Komentari se pri
+// učitavanju odbacuju, pa se inačice #WithComments ne prihvaćaju. Instrukcije za obradu dio su kanonskog oblika,
+// a one prije i poslije korijenskog elementa samo kad se kanonikalizira cijeli dokument (Document).
+// Dokument se učitava u jednostavno stablo jer reference potpisa mogu pokazivati na bilo koji dio dokumenta.
+// Učitava se kroz reader::Reader, pa vrijede ista ograničenja i odbijanje DTD-a kao pri parsiranju računa.
+
+use crate::{ParseError, limits::Limits, path::Path, reader::Reader};
+use std::collections::{BTreeMap, HashSet};
+use xml::{
+	attribute::OwnedAttribute,
+	name::OwnedName,
+	namespace::{NS_XML_PREFIX, NS_XMLNS_PREFIX, Namespace},
+	reader::XmlEvent,
+};
+
+pub const EXCLUSIVE: &str = "http://www.w3.org/2001/10/xml-exc-c14n#";
+pub const INCLUSIVE: &str = "http://www.w3.org/TR/2001/REC-xml-c14n-20010315";
+
+#[derive(Debug)]
+pub struct Element {
+	pub name: OwnedName,
+	pub attributes: Vec<OwnedAttribute>,
+	// Svi prostori imena vidljivi na ovom elementu
+	pub namespace: Namespace,
+	pub children: Vec<Node>,
+}
+
+#[derive(Debug)]
+pub enum Node {
+	Element(Element),
+	Text(String),
+	// Instrukcija za obradu, npr. <?xml-stylesheet href="a.xsl"?>
+	ProcessingInstruction { name: String, data: Option<String> },
+}
+
+// Korijenski element s instrukcijama za obradu izvan njega
+#[derive(Debug)]
+pub struct Document {
+	pub before: Vec<Node>,
+	pub root: Element,
+	pub after: Vec<Node>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Method {
+	// Prefiksi iz ec:InclusiveNamespaces/@PrefixList, "#default" označava zadani prostor imena
+	Exclusive { inclusive_prefixes: Vec<String> },
+	Inclusive,
+}
+
+impl Method {
+	pub fn from_algorithm(algorithm: &str) -> Option<Self> {
+		match algorithm {
+			EXCLUSIVE => Some(Self::Exclusive { inclusive_prefixes: Vec::new() }),
+			INCLUSIVE => Some(Self::Inclusive),
+			_ => None,
+		}
+	}
+}
+
+impl Document {
+	pub fn parse(document: &[u8], limits: &Limits) -> Result<Self, ParseError> {
+		let mut parser = Reader::new(document, limits);
+		let mut path = Path::default();
+
+		let mut stack: Vec<Element> = Vec::new();
+		let mut before = Vec::new();
+		let mut root = None;
+		let mut after = Vec::new();
+		loop {
+			match parser.next(&path)? {
+				XmlEvent::StartElement { name, attributes, namespace } => {
+					path.push(&name);
+					stack.push(Element { name, attributes, namespace, children: Vec::new() });
+				},
+				XmlEvent::EndElement { .. } => {
+					path.pop();
+					let element = stack.pop().unwrap();
+					match stack.last_mut() {
+						Some(parent) => parent.children.push(Node::Element(element)),
+						None => root = Some(element),
+					}
+				},
+				XmlEvent::Characters(text) | XmlEvent::Whitespace(text) | XmlEvent::CData(text) => {
+					if let Some(parent) = stack.last_mut() {
+						parent.children.push(Node::Text(text));
+					}
+				},
+				XmlEvent::ProcessingInstruction { name, data } => {
+					let node = Node::ProcessingInstruction { name, data };
+					match (stack.last_mut(), &root) {
+						(Some(parent), _) => parent.children.push(node),
+						(None, None) => before.push(node),
+						(None, Some(_)) => after.push(node),
+					}
+				},
+				e @ XmlEvent::EndDocument => {
+					let Some(root) = root else {
+						return Err(ParseError::unexpected(&parser, &path, &e));
+					};
+					return Ok(Self { before, root, after });
+				},
+				_ => (),
+			}
+		}
+	}
+
+	// Kanonski oblik cijelog dokumenta, instrukcije izvan korijenskog elementa odvojene su prijelomom retka
+	pub fn canonicalize(&self, method: &Method, exclude: Option<&Element>) -> Vec<u8> {
+		let mut out = String::new();
+		for node in &self.before {
+			write_node(&mut out, node, &BTreeMap::new(), method, exclude);
+			out.push('\n');
+		}
+		write_element(&mut out, &self.root, &BTreeMap::new(), method, exclude);
+		for node in &self.after {
+			out.push('\n');
+			write_node(&mut out, node, &BTreeMap::new(), method, exclude);
+		}
+		out.into_bytes()
+	}
+}
+
+impl Element {
+	pub fn parse(document: &[u8], limits: &Limits) -> Result<Self, ParseError> {
+		Ok(Document::parse(document, limits)?.root)
+	}
+
+	pub fn is(&self, namespace: &str, local_name: &str) -> bool {
+		self.name.local_name == local_name && self.name.namespace.as_deref() == Some(namespace)
+	}
+
+	// Atribut bez prostora imena
+	pub fn attribute(&self, local_name: &str) -> Option<&str> {
+		self.attributes
+			.iter()
+			.find(|x| x.name.namespace.is_none() && x.name.local_name == local_name)
+			.map(|x| x.value.as_str())
+	}
+
+	pub fn elements(&self) -> impl Iterator<Item = &Element> {
+		self.children.iter().filter_map(|x| match x {
+			Node::Element(e) => Some(e),
+			_ => None,
+		})
+	}
+
+	pub fn child(&self, namespace: &str, local_name: &str) -> Option<&Element> {
+		self.elements().find(|x| x.is(namespace, local_name))
+	}
+
+	pub fn text(&self) -> String {
+		let mut text = String::new();
+		for child in &self.children {
+			if let Node::Text(t) = child {
+				text.push_str(t);
+			}
+		}
+
+		text
+	}
+
+	// Prvi element u podstablu (uključujući ovaj) koji zadovoljava uvjet
+	pub fn find(&self, predicate: &dyn Fn(&Element) -> bool) -> Option<&Element> {
+		if predicate(self) {
+			return Some(self);
+		}
+
+		self.elements().find_map(|x| x.find(predicate))
+	}
+
+	pub fn find_by_id(&self, id: &str) -> Option<&Element> {
+		self.find(&|x| x.ids().any(|x| x == id))
+	}
+
+	// Prvi ID koji se u podstablu pojavljuje više puta; reference na takav ID nisu jednoznačne
+	pub fn duplicate_id(&self) -> Option<&str> {
+		let mut seen = HashSet::new();
+		self.first_duplicate_id(&mut seen)
+	}
+
+	fn first_duplicate_id<'a>(&'a self, seen: &mut HashSet<&'a str>) -> Option<&'a str> {
+		let ids: HashSet<&str> = self.ids().collect();
+		if let Some(id) = ids.into_iter().find(|x| !seen.insert(x)) {
+			return Some(id);
+		}
+
+		self.elements().find_map(|x| x.first_duplicate_id(seen))
+	}
+
+	fn ids(&self) -> impl Iterator<Item = &str> {
+		["Id", "ID", "id"].into_iter().filter_map(|x| self.attribute(x))
+	}
+
+	// Element je ovaj element ili se nalazi u njegovom podstablu
+	pub fn contains(&self, element: &Element) -> bool {
+		self.find(&|x| std::ptr::eq(x, element)).is_some()
+	}
+
+	// Kanonski oblik podstabla, bez elementa `exclude` (transformacija enveloped-signature)
+	pub fn canonicalize(&self, method: &Method, exclude: Option<&Element>) -> Vec<u8> {
+		let mut out = String::new();
+		write_element(&mut out, self, &BTreeMap::new(), method, exclude);
+		out.into_bytes()
+	}
+}
+
+fn qualified_name(name: &OwnedName) -> String {
+	match &name.prefix {
+		Some(prefix) => std::format!("{}:{}", prefix, name.local_name),
+		None => name.local_name.clone(),
+	}
+}
+
+fn write_element(
+	out: &mut String,
+	element: &Element,
+	rendered: &BTreeMap<String, String>,
+	method: &Method,
+	exclude: Option<&Element>,
+) {
+	let in_scope = |prefix: &str| element.namespace.get(prefix).unwrap_or("").to_owned();
+
+	let candidates: Vec<String> = match method {
+		Method::Exclusive { inclusive_prefixes } => {
+			let mut prefixes = vec![element.name.prefix.clone().unwrap_or_default()];
+			prefixes.extend(element.attributes.iter().filter_map(|x| x.name.prefix.clone()));
+			for prefix in inclusive_prefixes {
+				let prefix = if prefix == "#default" { "" } else { prefix.as_str() };
+				if element.namespace.get(prefix).is_some() {
+					prefixes.push(prefix.to_owned());
+				}
+			}
+			prefixes
+		},
+		Method::Inclusive => element.namespace.0.keys().cloned().collect(),
+	};
+
+	let mut declarations = BTreeMap::new();
+	for prefix in candidates {
+		if prefix == NS_XML_PREFIX || prefix == NS_XMLNS_PREFIX {
+			continue;
+		}
+
+		let uri = in_scope(&prefix);
+		let current = rendered.get(&prefix).map(String::as_str).unwrap_or("");
+		if prefix.is_empty() && uri.is_empty() {
+			if !current.is_empty() {
+				declarations.insert(prefix, uri);
+			}
+		} else if rendered.get(&prefix) != Some(&uri) {
+			declarations.insert(prefix, uri);
+		}
+	}
+
+	let mut attributes: Vec<&OwnedAttribute> = element.attributes.iter().collect();
+	attributes.sort_by(|a, b| {
+		let a_key = (a.name.namespace.as_deref().unwrap_or(""), &a.name.local_name);
+		let b_key = (b.name.namespace.as_deref().unwrap_or(""), &b.name.local_name);
+		a_key.cmp(&b_key)
+	});
+
+	out.push('<');
+	out.push_str(&qualified_name(&element.name));
+	for (prefix, uri) in &declarations {
+		if prefix.is_empty() {
+			out.push_str(" xmlns=\"");
+		} else {
+			out.push_str(" xmlns:");
+			out.push_str(prefix);
+			out.push_str("=\"");
+		}
+		escape_attribute(out, uri);
+		out.push('"');
+	}
+	for attribute in attributes {
+		out.push(' ');
+		out.push_str(&qualified_name(&attribute.name));
+		out.push_str("=\"");
+		escape_attribute(out, &attribute.value);
+		out.push('"');
+	}
+	out.push('>');
+
+	let mut rendered = rendered.clone();
+	rendered.extend(declarations);
+	for child in &element.children {
+		write_node(out, child, &rendered, method, exclude);
+	}
+
+	out.push_str("</");
+	out.push_str(&qualified_name(&element.name));
+	out.push('>');
+}
+
+fn write_node(
+	out: &mut String,
+	node: &Node,
+	rendered: &BTreeMap<String, String>,
+	method: &Method,
+	exclude: Option<&Element>,
+) {
+	match node {
+		Node::Element(e) if exclude.is_some_and(|x| std::ptr::eq(x, e)) => (),
+		Node::Element(e) => write_element(out, e, rendered, method, exclude),
+		Node::Text(text) => escape_text(out, text),
+		// Razmak iza imena nije dio podataka, a bez podataka se ne piše
+		Node::ProcessingInstruction { name, data } => {
+			out.push_str("<?");
+			out.push_str(name);
+			if let Some(data) = data.as_deref().map(str::trim_start).filter(|x| !x.is_empty()) {
+				out.push(' ');
+				out.push_str(data);
+			}
+			out.push_str("?>");
+		},
+	}
+}
+
+fn escape_text(out: &mut String, text: &str) {
+	for c in text.chars() {
+		match c {
+			'&' => out.push_str("&amp;"),
+			'<' => out.push_str("&lt;"),
+			'>' => out.push_str("&gt;"),
+			'\r' => out.push_str("&#xD;"),
+			c => out.push(c),
+		}
+	}
+}
+
+fn escape_attribute(out: &mut String, value: &str) {
+	for c in value.chars() {
+		match c {
+			'&' => out.push_str("&amp;"),
+			'<' => out.push_str("&lt;"),
+			'"' => out.push_str("&quot;"),
+			'\t' => out.push_str("&#x9;"),
+			'\n' => out.push_str("&#xA;"),
+			'\r' => out.push_str("&#xD;"),
+			c => out.push(c),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn parse(xml: &str) -> Element {
+		Element::parse(xml.as_bytes(), &Limits::default()).unwrap()
+	}
+
+	fn canonical(xml: &str, method: &Method) -> String {
+		String::from_utf8(parse(xml).canonicalize(method, None)).unwrap()
+	}
+
+	fn exclusive() -> Method {
+		Method::from_algorithm(EXCLUSIVE).unwrap()
+	}
+
+	#[test]
+	fn exclusive_renders_only_used_namespaces() {
+		let xml = r#"<a:root xmlns:a="urn:a" xmlns:b="urn:b"><a:child/></a:root>"#;
+		assert_eq!(canonical(xml, &exclusive()), r#"<a:root xmlns:a="urn:a"><a:child></a:child></a:root>"#);
+	}
+
+	#[test]
+	fn inclusive_renders_namespaces_in_scope() {
+		let xml = r#"<a:root xmlns:a="urn:a" xmlns:b="urn:b"><a:child/></a:root>"#;
+		assert_eq!(
+			canonical(xml, &Method::Inclusive),
+			r#"<a:root xmlns:a="urn:a" xmlns:b="urn:b"><a:child></a:child></a:root>"#
+		);
+	}
+
+	#[test]
+	fn exclusive_inclusive_prefixes() {
+		let xml = r#"<a:root xmlns:a="urn:a" xmlns:b="urn:b"/>"#;
+		let method = Method::Exclusive { inclusive_prefixes: vec!["b".to_owned()] };
+		assert_eq!(canonical(xml, &method), r#"<a:root xmlns:a="urn:a" xmlns:b="urn:b"></a:root>"#);
+	}
+
+	#[test]
+	fn attributes_sorted_and_escaped() {
+		let xml = "<root z=\"1\" a=\"&quot;&lt;&#9;\" xmlns:p=\"urn:p\" p:b=\"2\">a &amp; b &gt; c</root>";
+		assert_eq!(
+			canonical(xml, &exclusive()),
+			"<root xmlns:p=\"urn:p\" a=\"&quot;&lt;&#x9;\" z=\"1\" p:b=\"2\">a &amp; b &gt; c</root>"
+		);
+	}
+
+	#[test]
+	fn cdata_and_comments() {
+		let xml = "<root><!-- komentar --><![CDATA[<x>]]> y</root>";
+		assert_eq!(canonical(xml, &exclusive()), "<root>&lt;x&gt; y</root>");
+	}
+
+	#[test]
+	fn processing_instructions() {
+		let xml = "<?before x?><root><?pi  a  b ?><a/><?empty?><b><?p &lt;?></b></root><?after?>";
+		let expected = "<root><?pi a  b ?><a></a><?empty?><b><?p &lt;?></b></root>";
+		assert_eq!(canonical(xml, &exclusive()), expected);
+		assert_eq!(canonical(xml, &Method::Inclusive), expected);
+		assert_eq!(parse(xml).text(), "");
+
+		let document = Document::parse(xml.as_bytes(), &Limits::default()).unwrap();
+		let canonical = String::from_utf8(document.canonicalize(&exclusive(), None)).unwrap();
+		assert_eq!(canonical, std::format!("<?before x?>\n{}\n<?after?>", expected));
+	}
+
+	#[test]
+	fn excluded_element() {
+		let root = parse("<root><a>1</a><b>2</b></root>");
+		let b = root.elements().nth(1).unwrap();
+		let canonical = String::from_utf8(root.canonicalize(&exclusive(), Some(b))).unwrap();
+		assert_eq!(canonical, "<root><a>1</a></root>");
+	}
+
+	#[test]
+	fn with_comments_not_supported() {
+		assert_eq!(Method::from_algorithm("http://www.w3.org/2001/10/xml-exc-c14n#WithComments"), None);
+		assert_eq!(Method::from_algorithm("http://www.w3.org/TR/2001/REC-xml-c14n-20010315#WithComments"), None);
+		assert_eq!(Method::from_algorithm(INCLUSIVE), Some(Method::Inclusive));
+	}
+
+	#[test]
+	fn doctype_rejected() {
+		let error = Element::parse(b"<!DOCTYPE root []><root/>", &Limits::default()).unwrap_err();
+		assert!(matches!(error.kind, crate::ParseErrorKind::DoctypeNotAllowed));
+	}
+
+	#[test]
+	fn depth_limited() {
+		let limits = Limits { max_depth: 2, ..Limits::default() };
+		let error = Element::parse(b"<a><b><c/></b></a>", &limits).unwrap_err();
+		assert!(matches!(error.kind, crate::ParseErrorKind::LimitExceeded { .. }));
+	}
+
+	#[test]
+	fn duplicate_ids() {
+		let root = parse(r#"<root><a Id="x"/><b><c ID="x"/></b></root>"#);
+		assert_eq!(root.duplicate_id(), Some("x"));
+		assert_eq!(root.find_by_id("x").unwrap().name.local_name, "a");
+
+		let root = parse(r#"<root><a Id="x" ID="x"/><b id="y"/></root>"#);
+		assert_eq!(root.duplicate_id(), None);
+	}
+}
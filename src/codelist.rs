@@ -0,0 +1,265 @@
+// Popisi šifri iz norme EN 16931
+//
+// Popisi su u datotekama u codelist/ (šifra<TAB>hrvatski naziv<TAB>engleski naziv, iza kojih mogu doći dodatni
+// stupci), retci koji počinju s # su komentari. Iz svake datoteke build.rs generira enum sa šifrom kao varijantom,
+// npr. Unit::H87, a CodeList služi za traženje šifre kad se popis zna tek iz polja. Šifra koje nema u popisu
+// prikazuje se bez opisa.
+//
+// Popisi UN/ECE preporuke 20 i ISO 6523 ICD nisu potpuni: rec20.tsv sadrži jedinice koje se u računima uobičajeno
+// koriste, a icd.tsv sheme koje su i u popisu CEF EAS. Šifra dodana u datoteku postaje nova varijanta enuma.
+
+use crate::mapping::Field;
+
+include!(concat!(env!("OUT_DIR"), "/codelist.rs"));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize)]
+pub enum CodeList {
+	// UNTDID 1001, vrsta računa (BT-3)
+	InvoiceType,
+	// UNTDID 4461, način plaćanja (BT-81)
+	PaymentMeans,
+	// UNTDID 5305, kategorija PDV-a (BT-151)
+	VatCategory,
+	// UN/ECE preporuke 20 i 21, jedinica mjere (BT-130, BT-150)
+	Unit,
+	// ISO 4217, valuta (BT-5, BT-6 i atributi currencyID)
+	Currency,
+	// ISO 3166-1 alpha-2, država (BT-40, BT-55)
+	Country,
+	// ISO 6523 ICD, shema identifikatora stranke (schemeID)
+	Icd,
+	// CEF EAS, shema elektroničke adrese (schemeID uz BT-34 i BT-49)
+	Eas,
+	// VATEX, razlog oslobođenja od PDV-a (BT-121)
+	Vatex,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Code {
+	pub code: &'static str,
+	pub name_hr: &'static str,
+	pub name_en: &'static str,
+}
+
+impl CodeList {
+	pub const ALL: [Self; 9] = [
+		Self::InvoiceType,
+		Self::PaymentMeans,
+		Self::VatCategory,
+		Self::Unit,
+		Self::Currency,
+		Self::Country,
+		Self::Icd,
+		Self::Eas,
+		Self::Vatex,
+	];
+
+	// Oznaka popisa kako je navodi norma
+	pub fn name(self) -> &'static str {
+		match self {
+			Self::InvoiceType => "UNCL1001",
+			Self::PaymentMeans => "UNCL4461",
+			Self::VatCategory => "UNCL5305",
+			Self::Unit => "UNECERec20",
+			Self::Currency => "ISO 4217",
+			Self::Country => "ISO 3166-1",
+			Self::Icd => "ISO 6523 ICD",
+			Self::Eas => "CEF EAS",
+			Self::Vatex => "VATEX",
+		}
+	}
+
+	pub fn get(self, code: &str) -> Option<Code> {
+		match self {
+			Self::InvoiceType => InvoiceType::from_code(code).map(Code::from),
+			Self::PaymentMeans => PaymentMeans::from_code(code).map(Code::from),
+			Self::VatCategory => VatCategory::from_code(code).map(Code::from),
+			Self::Unit => Unit::from_code(code).map(Code::from),
+			Self::Currency => Currency::from_code(code).map(Code::from),
+			Self::Country => Country::from_code(code).map(Code::from),
+			Self::Icd => Icd::from_code(code).map(Code::from),
+			Self::Eas => Eas::from_code(code).map(Code::from),
+			Self::Vatex => Vatex::from_code(code).map(Code::from),
+		}
+	}
+
+	// Naziv i šifra, npr. "Komad (H87)", ili samo šifra ako je nema u popisu
+	pub fn label_hr(self, code: &str) -> String {
+		label(code, self.get(code).map(|x| x.name_hr))
+	}
+
+	pub fn label_en(self, code: &str) -> String {
+		label(code, self.get(code).map(|x| x.name_en))
+	}
+
+	// Popis šifri za polje iz tablice polja, None ako vrijednost polja nije šifra iz popisa
+	pub fn for_field(field: &Field) -> Option<Self> {
+		let attribute = field.path.rsplit_once("/@").map(|(_, x)| x);
+		let list = match (field.id, attribute) {
+			(_, Some("currencyID")) => Self::Currency,
+			(_, Some("unitCode")) => Self::Unit,
+			("BT-34" | "BT-49", Some("schemeID")) => Self::Eas,
+			(_, Some("schemeID")) => Self::Icd,
+			(_, Some(_)) => return None,
+			("BT-3", None) => Self::InvoiceType,
+			("BT-5" | "BT-6", None) => Self::Currency,
+			("BT-81", None) => Self::PaymentMeans,
+			("BT-151", None) => Self::VatCategory,
+			("BT-40" | "BT-55", None) => Self::Country,
+			("BT-121", None) => Self::Vatex,
+			_ => return None,
+		};
+
+		Some(list)
+	}
+}
+
+// Šifra iz popisa u vrijednosti polja, vidi Mapped::codes
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct CodeLabel {
+	#[serde(skip)]
+	pub field: &'static Field,
+	// Puna putanja, kao u Missing, npr. /Invoice/cac:InvoiceLine[1]/cbc:InvoicedQuantity/@unitCode
+	pub path: String,
+	pub list: CodeList,
+	pub code: String,
+	// None ako šifre nema u popisu
+	pub name_hr: Option<&'static str>,
+	pub name_en: Option<&'static str>,
+}
+
+impl CodeLabel {
+	pub(crate) fn new(list: CodeList, field: &'static Field, path: String, code: &str) -> Self {
+		let found = list.get(code);
+		Self {
+			field,
+			path,
+			list,
+			code: code.trim().to_owned(),
+			name_hr: found.map(|x| x.name_hr),
+			name_en: found.map(|x| x.name_en),
+		}
+	}
+
+	pub fn label_hr(&self) -> String {
+		label(&self.code, self.name_hr)
+	}
+
+	pub fn label_en(&self) -> String {
+		label(&self.code, self.name_en)
+	}
+}
+
+fn label(code: &str, name: Option<&str>) -> String {
+	let code = code.trim();
+	let Some(name) = name else {
+		return code.to_owned();
+	};
+
+	let mut chars = name.chars();
+	let first: String = chars.next().into_iter().flat_map(char::to_uppercase).collect();
+	std::format!("{}{} ({})", first, chars.as_str(), code)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn typed_codes() {
+		assert_eq!(Unit::from_code(" H87 "), Some(Unit::H87));
+		assert_eq!(Unit::H87.name_hr(), "komad");
+		assert_eq!(InvoiceType::from_code("380").map(InvoiceType::code), Some("380"));
+		assert_eq!(Vatex::from_code("VATEX-EU-79-C"), Some(Vatex::VATEX_EU_79_C));
+		assert_eq!(Currency::from_code("XYZ"), None);
+		assert_eq!(
+			Country::ALL.len(),
+			include_str!("codelist/iso3166.tsv")
+				.lines()
+				.filter(|x| !x.starts_with('#'))
+				.count()
+		);
+	}
+
+	#[test]
+	fn minor_units() {
+		assert_eq!(Currency::EUR.minor_units(), 2);
+		assert_eq!(Currency::JPY.minor_units(), 0);
+		assert_eq!(Currency::KWD.minor_units(), 3);
+		assert_eq!(Currency::CLF.minor_units(), 4);
+	}
+
+	#[test]
+	fn labels() {
+		assert_eq!(CodeList::Unit.label_hr("H87"), "Komad (H87)");
+		assert_eq!(CodeList::PaymentMeans.label_hr("30"), "Kreditni transfer (30)");
+		assert_eq!(CodeList::Unit.label_en("XYZ"), "XYZ");
+	}
+
+	// Za svaki popis barem jedno polje iz tablice polja dobiva naziv
+	#[test]
+	fn field_labels() {
+		let xml = include_str!("../tests/data/invoice.xml").replace(
+			"<cbc:DocumentCurrencyCode>EUR</cbc:DocumentCurrencyCode>",
+			"<cbc:DocumentCurrencyCode>EUR</cbc:DocumentCurrencyCode><cbc:TaxCurrencyCode>USD</cbc:TaxCurrencyCode>",
+		);
+		let xml_data = crate::from_str(&xml).unwrap();
+		let mut labels = xml_data.code_labels();
+		labels.extend(xml_data.invoice_lines[0].code_labels(1));
+		let find = |path: &str| {
+			labels
+				.iter()
+				.find(|x| x.path == path)
+				.unwrap_or_else(|| panic!("{}", path))
+		};
+
+		let party = "/Invoice/cac:AccountingCustomerParty/cac:Party";
+		let line = "/Invoice/cac:InvoiceLine[1]";
+		let cases = [
+			("/Invoice/cbc:InvoiceTypeCode", CodeList::InvoiceType, "Račun (380)"),
+			("/Invoice/cbc:TaxCurrencyCode", CodeList::Currency, "Američki dolar (USD)"),
+			("/Invoice/cac:TaxTotal[1]/cbc:TaxAmount/@currencyID", CodeList::Currency, "Euro (EUR)"),
+			("/Invoice/cac:PaymentMeans/cbc:PaymentMeansCode", CodeList::PaymentMeans, "Kreditni transfer (30)"),
+			(
+				&std::format!("{}/cac:PostalAddress/cac:Country/cbc:IdentificationCode", party),
+				CodeList::Country,
+				"Hrvatska (HR)",
+			),
+			(
+				&std::format!("{}/cac:PartyIdentification/cbc:ID/@schemeID", party),
+				CodeList::Icd,
+				"GLN, globalni broj lokacije (0088)",
+			),
+			(&std::format!("{}/cbc:EndpointID/@schemeID", party), CodeList::Eas, "OIB (Hrvatska) (9934)"),
+			(&std::format!("{}/cbc:InvoicedQuantity/@unitCode", line), CodeList::Unit, "Komad (H87)"),
+			(
+				&std::format!("{}/cac:Item/cac:ClassifiedTaxCategory/cbc:ID", line),
+				CodeList::VatCategory,
+				"Standardna stopa (S)",
+			),
+		];
+		for (path, list, label) in cases {
+			let code = find(path);
+			assert_eq!((code.list, code.label_hr().as_str()), (list, label), "{}", path);
+		}
+		assert_eq!(find("/Invoice/cbc:InvoiceTypeCode").label_en(), "Commercial invoice (380)");
+		assert_eq!(find("/Invoice/cbc:InvoiceTypeCode").field.id, "BT-3");
+
+		// BT-121 još nije u tablici polja, pa se popis VATEX provjerava na opisu polja
+		static EXEMPTION: Field = Field {
+			id: "BT-121",
+			path: "cbc:TaxExemptionReasonCode",
+			cardinality: crate::mapping::Cardinality { min: 0, max: Some(1) },
+			label_hr: "Šifra razloga oslobođenja od PDV-a",
+			label_en: "VAT exemption reason code",
+			whitespace: crate::mapping::Whitespace::Collapse,
+			key: "tax_exemption_reason_code",
+			children: &[],
+			attributes: &[],
+		};
+		let list = CodeList::for_field(&EXEMPTION).unwrap();
+		let code = CodeLabel::new(list, &EXEMPTION, String::new(), "VATEX-EU-132");
+		assert_eq!(code.list, CodeList::Vatex);
+		assert_eq!(code.label_en(), "Exempt based on article 132 of Council Directive 2006/112/EC (VATEX-EU-132)");
+	}
+}
@@ -0,0 +1,220 @@
+// Datumi u obliku xsd:date
+//
+// Prihvaća se GGGG-MM-DD s neobaveznom vremenskom zonom (Z, +01:00 ili -05:00), a nemogući datumi poput
+// 2025-02-30 se odbijaju. Izvorni zapis se čuva, pa se u JSON-u i pri ponovnom ispisu datum ne mijenja.
+
+use crate::mapping::Value;
+use std::{
+	cmp::Ordering,
+	fmt::{self, Display},
+	str::FromStr,
+};
+use time::{Duration, Month, UtcOffset};
+use xml::attribute::OwnedAttribute;
+
+#[derive(Debug, Clone)]
+pub struct Date {
+	date: time::Date,
+	offset: Option<UtcOffset>,
+	lexical: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DateError(pub String);
+
+impl Display for DateError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "neispravan datum: {}", self.0)
+	}
+}
+
+impl std::error::Error for DateError {}
+
+impl Date {
+	// Kalendarski datum, bez vremenske zone
+	pub fn date(&self) -> time::Date {
+		self.date
+	}
+
+	// Vremenska zona, ako je navedena
+	pub fn offset(&self) -> Option<UtcOffset> {
+		self.offset
+	}
+
+	// Izvorni zapis iz dokumenta
+	pub fn as_str(&self) -> &str {
+		&self.lexical
+	}
+
+	// Broj dana do drugog datuma, negativan ako je drugi datum raniji
+	pub fn days_until(&self, other: &Date) -> i64 {
+		(other.date - self.date).whole_days()
+	}
+
+	// Datum nakon zadanog broja dana, npr. dospijeće 30 dana od izdavanja
+	pub fn plus_days(&self, days: i64) -> Option<time::Date> {
+		self.date.checked_add(Duration::days(days))
+	}
+}
+
+impl FromStr for Date {
+	type Err = DateError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let error = || DateError(s.to_owned());
+		let text = s.trim();
+
+		// Godina ima barem četiri znamenke i može biti negativna
+		let (negative, rest) = match text.strip_prefix('-') {
+			Some(rest) => (true, rest),
+			None => (false, text),
+		};
+		let (year, rest) = rest.split_once('-').ok_or_else(error)?;
+		if year.len() < 4 {
+			return Err(error());
+		}
+		let (month_day, zone) = rest.split_at_checked(5).ok_or_else(error)?;
+		let (month, day) = month_day.split_once('-').ok_or_else(error)?;
+
+		let year: i32 = digits(year).ok_or_else(error)?;
+		let month = Month::try_from(digits::<u8>(month).ok_or_else(error)?).map_err(|_| error())?;
+		let day = digits(day).ok_or_else(error)?;
+		let date =
+			time::Date::from_calendar_date(if negative { -year } else { year }, month, day).map_err(|_| error())?;
+
+		Ok(Self {
+			date,
+			offset: parse_offset(zone).ok_or_else(error)?,
+			lexical: text.to_owned(),
+		})
+	}
+}
+
+// Samo znamenke, bez predznaka i razmaka koje dopušta str::parse
+fn digits<T: FromStr>(text: &str) -> Option<T> {
+	if text.is_empty() || !text.bytes().all(|x| x.is_ascii_digit()) {
+		return None;
+	}
+	text.parse().ok()
+}
+
+// Z, +hh:mm ili -hh:mm, najviše 14:00; Some(None) ako zona nije navedena
+fn parse_offset(zone: &str) -> Option<Option<UtcOffset>> {
+	if zone.is_empty() {
+		return Some(None);
+	}
+	if zone == "Z" {
+		return Some(Some(UtcOffset::UTC));
+	}
+
+	let (sign, rest) = match zone.as_bytes().first()? {
+		b'+' => (1, &zone[1..]),
+		b'-' => (-1, &zone[1..]),
+		_ => return None,
+	};
+	let (hours, minutes) = rest.split_once(':')?;
+	if hours.len() != 2 || minutes.len() != 2 {
+		return None;
+	}
+	let hours: i8 = digits(hours)?;
+	let minutes: i8 = digits(minutes)?;
+	if hours > 14 || minutes > 59 || hours == 14 && minutes > 0 {
+		return None;
+	}
+
+	UtcOffset::from_hms(sign * hours, sign * minutes, 0).ok().map(Some)
+}
+
+impl Display for Date {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(&self.lexical)
+	}
+}
+
+// Usporedba po kalendarskom datumu, vremenska zona se ne uzima u obzir
+impl PartialEq for Date {
+	fn eq(&self, other: &Self) -> bool {
+		self.date == other.date
+	}
+}
+
+impl Eq for Date {}
+
+impl PartialOrd for Date {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl Ord for Date {
+	fn cmp(&self, other: &Self) -> Ordering {
+		self.date.cmp(&other.date)
+	}
+}
+
+impl Value for Date {
+	fn parse(text: &str, _: &[OwnedAttribute]) -> Option<Self> {
+		text.parse().ok()
+	}
+}
+
+impl serde::Serialize for Date {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(&self.lexical)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn date(text: &str) -> Result<Date, DateError> {
+		text.parse()
+	}
+
+	#[test]
+	fn valid_dates() {
+		let d = date("2025-01-15").unwrap();
+		assert_eq!(d.date(), time::Date::from_calendar_date(2025, Month::January, 15).unwrap());
+		assert_eq!(d.offset(), None);
+		assert_eq!(date("2025-01-15Z").unwrap().offset(), Some(UtcOffset::UTC));
+		assert_eq!(date("2025-01-15+01:00").unwrap().offset(), UtcOffset::from_hms(1, 0, 0).ok());
+		assert_eq!(date("2025-01-15-05:30").unwrap().offset(), UtcOffset::from_hms(-5, -30, 0).ok());
+		assert_eq!(date(" 2024-02-29 ").unwrap().as_str(), "2024-02-29");
+	}
+
+	#[test]
+	fn invalid_dates() {
+		for text in [
+			"",
+			"2025",
+			"25-01-15",
+			"2025-1-15",
+			"2025-02-30",
+			"2025-13-01",
+			"2025-01-15+15:00",
+			"2025-01-15+01",
+			"2025-01-15T10:00:00",
+			"+2025-01-15",
+		] {
+			assert!(date(text).is_err(), "{}", text);
+		}
+	}
+
+	#[test]
+	fn non_ascii_does_not_panic() {
+		for text in [
+			"2025-01-0č", "2025-0č", "2025-č1-15", "2025-01-15+0č:00", "2025-01-15č", "2025-€",
+		] {
+			assert_eq!(date(text).unwrap_err(), DateError(text.to_owned()));
+		}
+	}
+
+	#[test]
+	fn non_ascii_is_invalid_value() {
+		let xml = include_str!("../tests/data/invoice.xml")
+			.replace("<cbc:IssueDate>2025-01-15</cbc:IssueDate>", "<cbc:IssueDate>2025-01-0č</cbc:IssueDate>");
+		let error = crate::from_str(&xml).unwrap_err();
+		assert!(matches!(error.kind, crate::ParseErrorKind::InvalidValue { ref value, .. } if value == "2025-01-0č"));
+	}
+}
@@ -0,0 +1,435 @@
+// Točni decimalni brojevi za iznose i količine
+//
+// Vrijednost se drži kao cijeli broj i broj decimala, pa se zbrajanje i uspoređivanje rade bez gubitka preciznosti.
+// Broj decimala iz dokumenta se čuva, 100.50 se ispisuje kao 100.50, a ne 100.5.
+//
+// Računanje je samo kroz checked_add, checked_sub i checked_mul, koji vraćaju None kad rezultat ima više od
+// MAX_DIGITS znamenki, npr. za umnožak dvaju najvećih brojeva koje parser prihvaća.
+
+use crate::mapping::{Value, attribute};
+use std::{
+	cmp::Ordering,
+	fmt::{self, Display},
+	ops::Neg,
+	str::FromStr,
+};
+use xml::attribute::OwnedAttribute;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Decimal {
+	mantissa: i128,
+	scale: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecimalError(pub String);
+
+impl Display for DecimalError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "neispravan decimalni broj: {}", self.0)
+	}
+}
+
+impl std::error::Error for DecimalError {}
+
+impl Decimal {
+	pub const ZERO: Self = Self { mantissa: 0, scale: 0 };
+
+	pub fn new(mantissa: i128, scale: u32) -> Self {
+		Self { mantissa, scale }
+	}
+
+	// Broj decimala, kako je zapisan u dokumentu ili nastao računanjem
+	pub fn scale(&self) -> u32 {
+		self.scale
+	}
+
+	pub fn is_zero(&self) -> bool {
+		self.mantissa == 0
+	}
+
+	pub fn is_negative(&self) -> bool {
+		self.mantissa < 0
+	}
+
+	pub fn abs(self) -> Self {
+		Self { mantissa: self.mantissa.abs(), ..self }
+	}
+
+	// Zaokruživanje na zadani broj decimala, polovice dalje od nule (0.125 -> 0.13, -0.125 -> -0.13)
+	// Ako se decimale ne mogu dopuniti nulama bez prekoračenja, vraća se ista vrijednost s manje decimala
+	pub fn round(self, scale: u32) -> Self {
+		if scale >= self.scale {
+			return self.rescale(scale).unwrap_or(self);
+		}
+
+		// Vrijednost je manja od pola zadnje zadane decimale
+		let Some(divisor) = pow10(self.scale - scale) else {
+			return Self { mantissa: 0, scale };
+		};
+		let quotient = self.mantissa / divisor;
+		let remainder = self.mantissa % divisor;
+		let mantissa = match remainder.abs() >= divisor - remainder.abs() {
+			true => quotient + self.mantissa.signum(),
+			false => quotient,
+		};
+		Self { mantissa, scale }
+	}
+
+	// Zbroj, None ako rezultat prelazi MAX_DIGITS znamenki
+	pub fn checked_add(self, other: Self) -> Option<Self> {
+		let scale = self.scale.max(other.scale);
+		let a = self.rescale(scale)?.mantissa;
+		let b = other.rescale(scale)?.mantissa;
+		Self::bounded(a.checked_add(b)?, scale)
+	}
+
+	pub fn checked_sub(self, other: Self) -> Option<Self> {
+		self.checked_add(-other)
+	}
+
+	// Broj decimala umnoška je zbroj decimala faktora, 2.5 * 1.25 = 3.125
+	pub fn checked_mul(self, other: Self) -> Option<Self> {
+		Self::bounded(self.mantissa.checked_mul(other.mantissa)?, self.scale + other.scale)
+	}
+
+	fn bounded(mantissa: i128, scale: u32) -> Option<Self> {
+		let max = pow10(MAX_DIGITS)?;
+		(mantissa.unsigned_abs() < max.unsigned_abs() && scale <= MAX_DIGITS).then_some(Self { mantissa, scale })
+	}
+
+	// Ista vrijednost s većim brojem decimala
+	fn rescale(self, scale: u32) -> Option<Self> {
+		let mantissa = self.mantissa.checked_mul(pow10(scale - self.scale)?)?;
+		Some(Self { mantissa, scale })
+	}
+
+	// Cijeli dio i decimale kao tekst, bez predznaka
+	pub(crate) fn digits(&self) -> (String, String) {
+		let digits = self.mantissa.unsigned_abs().to_string();
+		let scale = self.scale as usize;
+		if digits.len() > scale {
+			let (int, frac) = digits.split_at(digits.len() - scale);
+			(int.to_owned(), frac.to_owned())
+		} else {
+			("0".to_owned(), std::format!("{:0>width$}", digits, width = scale))
+		}
+	}
+}
+
+// Najviše znamenki cijelog dijela i decimala koje se prihvaćaju iz dokumenta
+const MAX_INTEGER_DIGITS: usize = 20;
+const MAX_SCALE: u32 = 18;
+// Najviše znamenki rezultata računanja, koliko stane u i128
+const MAX_DIGITS: u32 = 38;
+
+fn pow10(exponent: u32) -> Option<i128> {
+	10i128.checked_pow(exponent)
+}
+
+// Leksički oblik xsd:decimal: predznak, znamenke i decimalna točka, bez eksponenta, npr. -1234.50 ili .5
+impl FromStr for Decimal {
+	type Err = DecimalError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let error = || DecimalError(s.to_owned());
+		let text = s.trim();
+		let (negative, unsigned) = match text.as_bytes().first() {
+			Some(b'-') => (true, &text[1..]),
+			Some(b'+') => (false, &text[1..]),
+			_ => (false, text),
+		};
+		let (int, frac) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+		if int.is_empty() && frac.is_empty() || !(int.bytes().chain(frac.bytes()).all(|x| x.is_ascii_digit())) {
+			return Err(error());
+		}
+		// Svaka dva broja unutar ovih granica mogu se svesti na isti broj decimala bez prekoračenja
+		if int.trim_start_matches('0').len() > MAX_INTEGER_DIGITS || frac.len() > MAX_SCALE as usize {
+			return Err(error());
+		}
+
+		let mantissa = int
+			.bytes()
+			.chain(frac.bytes())
+			.fold(0i128, |x, digit| x * 10 + i128::from(digit - b'0'));
+		let scale = frac.len() as u32;
+
+		Ok(Self { mantissa: if negative { -mantissa } else { mantissa }, scale })
+	}
+}
+
+impl Display for Decimal {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let (int, frac) = self.digits();
+		let sign = if self.is_negative() { "-" } else { "" };
+		match frac.is_empty() {
+			true => write!(f, "{}{}", sign, int),
+			false => write!(f, "{}{}.{}", sign, int, frac),
+		}
+	}
+}
+
+// Usporedba po vrijednosti, 1.0 i 1.00 su jednaki
+impl PartialEq for Decimal {
+	fn eq(&self, other: &Self) -> bool {
+		self.cmp(other) == Ordering::Equal
+	}
+}
+
+impl Eq for Decimal {}
+
+impl PartialOrd for Decimal {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl Ord for Decimal {
+	fn cmp(&self, other: &Self) -> Ordering {
+		let scale = self.scale.max(other.scale);
+		match (self.rescale(scale), other.rescale(scale)) {
+			(Some(a), Some(b)) => a.mantissa.cmp(&b.mantissa),
+			// Broj koji se ne može svesti na iste decimale po apsolutnoj je vrijednosti veći od drugog
+			(None, _) => self.mantissa.cmp(&0),
+			(_, None) => 0.cmp(&other.mantissa),
+		}
+	}
+}
+
+impl Neg for Decimal {
+	type Output = Self;
+
+	fn neg(self) -> Self {
+		Self { mantissa: -self.mantissa, ..self }
+	}
+}
+
+// U JSON-u kao tekst, kako se ne bi izgubila preciznost
+impl serde::Serialize for Decimal {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.collect_str(self)
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CurrencyError {
+	// Iznos nije u valuti računa, navedenoj u greški
+	NotDocumentCurrency(String),
+	// Valuta obračuna PDV-a razlikuje se od valute računa, a ukupni iznos PDV-a u njoj (BT-111) nije naveden
+	MissingTaxTotal,
+}
+
+impl Display for CurrencyError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::NotDocumentCurrency(currency) => write!(f, "iznos nije u valuti računa {}", currency),
+			Self::MissingTaxTotal => write!(f, "nedostaje ukupni iznos PDV-a u valuti obračuna PDV-a (BT-111)"),
+		}
+	}
+}
+
+impl std::error::Error for CurrencyError {}
+
+// Iznos s valutom iz atributa currencyID
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Amount {
+	pub value: Decimal,
+	pub currency: Option<String>,
+}
+
+impl Amount {
+	pub fn new(value: Decimal, currency: Option<String>) -> Self {
+		Self { value, currency }
+	}
+
+	// Zbroj dvaju iznosa, None ako su u različitim valutama ili zbroj prelazi opseg
+	pub fn checked_add(&self, other: &Self) -> Option<Self> {
+		Some(Self::new(self.value.checked_add(other.value)?, self.common_currency(other)?))
+	}
+
+	pub fn checked_sub(&self, other: &Self) -> Option<Self> {
+		Some(Self::new(self.value.checked_sub(other.value)?, self.common_currency(other)?))
+	}
+
+	// Iznos puta količina ili stopa, npr. cijena * količina
+	pub fn checked_mul(&self, factor: Decimal) -> Option<Self> {
+		Some(Self::new(self.value.checked_mul(factor)?, self.currency.clone()))
+	}
+
+	// Iznos bez valute poprima valutu drugog iznosa
+	fn common_currency(&self, other: &Self) -> Option<Option<String>> {
+		match (&self.currency, &other.currency) {
+			(Some(a), Some(b)) if a != b => None,
+			(a, b) => Some(a.clone().or_else(|| b.clone())),
+		}
+	}
+}
+
+// Iznosi u različitim valutama nisu usporedivi
+impl PartialOrd for Amount {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		(self.currency == other.currency).then(|| self.value.cmp(&other.value))
+	}
+}
+
+impl Neg for Amount {
+	type Output = Self;
+
+	fn neg(self) -> Self {
+		Self::new(-self.value, self.currency)
+	}
+}
+
+impl Display for Amount {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match &self.currency {
+			Some(currency) => write!(f, "{} {}", self.value, currency),
+			None => write!(f, "{}", self.value),
+		}
+	}
+}
+
+impl Value for Amount {
+	const ATTRIBUTES: &'static [&'static str] = &["currencyID"];
+
+	fn parse(text: &str, attributes: &[OwnedAttribute]) -> Option<Self> {
+		Some(Self::new(text.parse().ok()?, attribute(attributes, "currencyID")))
+	}
+}
+
+impl serde::Serialize for Amount {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		self.value.serialize(serializer)
+	}
+}
+
+// Količina s jedinicom mjere iz atributa unitCode
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Quantity {
+	pub value: Decimal,
+	pub unit: Option<String>,
+}
+
+impl Quantity {
+	pub fn new(value: Decimal, unit: Option<String>) -> Self {
+		Self { value, unit }
+	}
+
+	// Cijena puta količina
+	pub fn checked_mul(&self, price: &Amount) -> Option<Amount> {
+		price.checked_mul(self.value)
+	}
+}
+
+impl PartialOrd for Quantity {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		(self.unit == other.unit).then(|| self.value.cmp(&other.value))
+	}
+}
+
+impl Display for Quantity {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match &self.unit {
+			Some(unit) => write!(f, "{} {}", self.value, unit),
+			None => write!(f, "{}", self.value),
+		}
+	}
+}
+
+impl Value for Quantity {
+	const ATTRIBUTES: &'static [&'static str] = &["unitCode"];
+
+	fn parse(text: &str, attributes: &[OwnedAttribute]) -> Option<Self> {
+		Some(Self::new(text.parse().ok()?, attribute(attributes, "unitCode")))
+	}
+}
+
+impl serde::Serialize for Quantity {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		self.value.serialize(serializer)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn decimal(text: &str) -> Decimal {
+		text.parse().unwrap()
+	}
+
+	#[test]
+	fn parse_and_display() {
+		assert_eq!(decimal("100.50").to_string(), "100.50");
+		assert_eq!(decimal("-.5").to_string(), "-0.5");
+		assert_eq!(decimal("+007").to_string(), "7");
+		for text in [
+			"",
+			".",
+			"1e5",
+			"1.2.3",
+			"- 1",
+			"1,5",
+			"123456789012345678901",
+			"0.1234567890123456789",
+		] {
+			assert!(text.parse::<Decimal>().is_err(), "{}", text);
+		}
+	}
+
+	#[test]
+	fn compare_by_value() {
+		assert_eq!(decimal("1.0"), decimal("1.00"));
+		assert!(decimal("-0.01") < decimal("0"));
+		assert!(decimal("99999999999999999999") > decimal("0.000000000000000001"));
+	}
+
+	#[test]
+	fn arithmetic() {
+		assert_eq!(decimal("2.5").checked_mul(decimal("1.25")).unwrap().to_string(), "3.125");
+		assert_eq!(decimal("1.1").checked_add(decimal("2.05")).unwrap().to_string(), "3.15");
+		assert_eq!(decimal("1").checked_sub(decimal("2.50")).unwrap().to_string(), "-1.50");
+	}
+
+	#[test]
+	fn overflow_is_none() {
+		let max = decimal("99999999999999999999.999999999999999999");
+		assert_eq!(max.checked_add(max), None);
+		assert_eq!((-max).checked_sub(max), None);
+		let hundred = decimal("100.000000000000000000");
+		assert_eq!(hundred.checked_mul(hundred), None);
+		assert_eq!(max.checked_mul(max), None);
+	}
+
+	#[test]
+	fn compare_values_that_cannot_be_aligned() {
+		// 10^20 s 36 decimala ne stane u i128
+		let big = decimal("99999999999999999999");
+		let small = decimal("0.000000000000000001")
+			.checked_mul(decimal("0.000000000000000001"))
+			.unwrap();
+		assert_eq!(small.scale(), 36);
+		assert!(big > small);
+		assert!(-big < small);
+		assert!(small < big);
+	}
+
+	#[test]
+	fn round_half_away_from_zero() {
+		assert_eq!(decimal("0.125").round(2).to_string(), "0.13");
+		assert_eq!(decimal("-0.125").round(2).to_string(), "-0.13");
+		assert_eq!(decimal("0.124").round(2).to_string(), "0.12");
+		assert_eq!(decimal("100").round(2).to_string(), "100.00");
+		assert_eq!(decimal("99999999999999999999").round(30), decimal("99999999999999999999"));
+	}
+
+	#[test]
+	fn amounts_in_different_currencies() {
+		let eur = Amount::new(decimal("1"), Some("EUR".to_owned()));
+		let usd = Amount::new(decimal("1"), Some("USD".to_owned()));
+		let none = Amount::new(decimal("2"), None);
+		assert_eq!(eur.checked_add(&usd), None);
+		assert_eq!(eur.checked_add(&none).unwrap(), Amount::new(decimal("3"), Some("EUR".to_owned())));
+		assert_eq!(eur.partial_cmp(&usd), None);
+	}
+}
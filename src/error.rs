@@ -0,0 +1,101 @@
+use crate::{limits::Limit, mapping::Field, path::Path};
+use xml::{common::Position, reader::XmlEvent};
+
+#[derive(Debug)]
+pub struct ParseError {
+	// Putanja elementa na kojem je nastala greška, npr. /Invoice/cac:InvoiceLine/cbc:InvoicedQuantity
+	pub path: String,
+	// Redak i stupac u dokumentu, počevši od 1
+	pub line: u64,
+	pub column: u64,
+	pub kind: ParseErrorKind,
+}
+
+#[derive(Debug)]
+pub enum ParseErrorKind {
+	// XML nije ispravno oblikovan
+	MalformedXml(xml::reader::Error),
+	// Nedostaje obavezni atribut, npr. unitCode ili schemeID
+	MissingAttribute(&'static str),
+	// Događaj koji na tom mjestu nije očekivan, npr. podelement umjesto teksta
+	UnexpectedEvent(String),
+	// Korijenski element nije Invoice
+	UnsupportedRoot(String),
+	// Dokument prelazi jedno od ograničenja iz Limits
+	LimitExceeded { limit: Limit, max: u64 },
+	// Dokument sadrži DTD, koji se zbog entiteta ne prihvaća
+	DoctypeNotAllowed,
+	// Vrijednost nije ispravnog oblika za tip polja, npr. iznos koji nije decimalni broj
+	InvalidValue { field: &'static Field, value: String },
+}
+
+impl ParseError {
+	pub(crate) fn new(parser: &impl Position, path: &Path, kind: ParseErrorKind) -> Self {
+		let position = parser.position();
+		Self {
+			path: path.as_str().to_owned(),
+			line: position.row + 1,
+			column: position.column + 1,
+			kind,
+		}
+	}
+
+	pub(crate) fn unexpected(parser: &impl Position, path: &Path, event: &XmlEvent) -> Self {
+		let event = match event {
+			XmlEvent::StartElement { name, .. } => std::format!("početak elementa {}", name),
+			XmlEvent::EndElement { name } => std::format!("kraj elementa {}", name),
+			XmlEvent::EndDocument => "kraj dokumenta".to_owned(),
+			e => std::format!("{:?}", e),
+		};
+
+		Self::new(parser, path, ParseErrorKind::UnexpectedEvent(event))
+	}
+
+	pub(crate) fn malformed(path: &Path, e: xml::reader::Error) -> Self {
+		let position = e.position();
+		Self {
+			path: path.as_str().to_owned(),
+			line: position.row + 1,
+			column: position.column + 1,
+			kind: ParseErrorKind::MalformedXml(e),
+		}
+	}
+}
+
+impl std::fmt::Display for ParseError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}:{} ({}): ", self.line, self.column, self.path)?;
+		match &self.kind {
+			// Položaj je već ispisan, pa ispisujemo samo opis greške
+			ParseErrorKind::MalformedXml(e) => match e.kind() {
+				xml::reader::ErrorKind::Syntax(msg) => write!(f, "neispravan XML: {}", msg),
+				xml::reader::ErrorKind::Io(e) => write!(f, "neispravan XML: {}", e),
+				xml::reader::ErrorKind::Utf8(e) => write!(f, "neispravan XML: {}", e),
+				xml::reader::ErrorKind::UnexpectedEof => write!(f, "neispravan XML: neočekivan kraj dokumenta"),
+				_ => write!(f, "neispravan XML: {}", e),
+			},
+			ParseErrorKind::MissingAttribute(name) => write!(f, "nedostaje obavezni atribut {}", name),
+			ParseErrorKind::UnexpectedEvent(event) => write!(f, "neočekivan {}", event),
+			ParseErrorKind::UnsupportedRoot(root) => write!(f, "nepodržan korijenski element {}", root),
+			ParseErrorKind::LimitExceeded { limit, max } => {
+				write!(f, "prekoračeno ograničenje: {} (najviše {})", limit, max)
+			},
+			ParseErrorKind::DoctypeNotAllowed => write!(f, "DTD nije dopušten"),
+			ParseErrorKind::InvalidValue { field, value } if field.id.is_empty() => {
+				write!(f, "neispravna vrijednost {}: \"{}\"", field.label_hr, value)
+			},
+			ParseErrorKind::InvalidValue { field, value } => {
+				write!(f, "neispravna vrijednost {} {}: \"{}\"", field.id, field.label_hr, value)
+			},
+		}
+	}
+}
+
+impl std::error::Error for ParseError {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match &self.kind {
+			ParseErrorKind::MalformedXml(e) => Some(e),
+			_ => None,
+		}
+	}
+}
@@ -0,0 +1,180 @@
+// Sadržaj ext:UBLExtensions
+//
+// Polja HRFISK20Data opisana su u tablici polja kao i ostatak računa, a putanja do HRFISK20Data navodi se u XmlData.
+//
+// Prostor imena urn:mfin.gov.hr:schema:xsd:HRExtensionAggregateComponents-1 i imena elemenata HRFISK20Data nisu
+// provjereni prema objavljenoj shemi: XSD proširenja nije u repozitoriju, Specifikacija upotrebe EN-UBL-HR ih ne
+// navodi, a tests/data/invoice.xml je izrađen ručno, ne preuzet sa stvarnog e-Računa. Dok se ne provjere prema
+// shemi i stvarnom računu, podelementi HRFISK20Data koje ne prepoznajemo uvijek se čuvaju kao izvorni XML u
+// HrExtension::unknown, pa se razlika u imenima vidi u izlazu.
+
+use crate::mapping::{self, mapped};
+
+mapped! {
+	#[derive(Debug, Default, serde::Serialize)]
+	pub struct HrExtension {
+		#[bt("", "hrextac:OperatorCode", 0..1, "Oznaka operatera", "Operator code")]
+		pub operator_code: Option<String>,
+		#[bt("", "hrextac:OperatorOIB", 0..1, "OIB operatera", "Operator OIB")]
+		pub operator_oib: Option<String>,
+		#[bt("", "hrextac:BusinessPremisesCode", 0..1, "Oznaka poslovnog prostora", "Business premises code")]
+		pub business_premises_code: Option<String>,
+		#[bt("", "hrextac:DeviceCode", 0..1, "Oznaka naplatnog uređaja", "Device code")]
+		pub device_code: Option<String>,
+		#[bt("", "hrextac:IssuerSecurityCode", 0..1, "Zaštitni kod izdavatelja (ZKI)", "Issuer security code")]
+		pub issuer_security_code: Option<String>,
+		#[bt(
+			"",
+			"hrextac:UniqueInvoiceIdentifier",
+			0..1,
+			"Jedinstveni identifikator računa (JIR)",
+			"Unique invoice identifier"
+		)]
+		pub unique_invoice_identifier: Option<String>,
+		#[bt("", "hrextac:VATOnPayment", 0..1, "Obračun prema naplaćenoj naknadi", "VAT on payment")]
+		pub vat_on_payment: Option<bool>,
+	}
+	extra {
+		// Podelementi HRFISK20Data koji nisu u tablici polja, kao izvorni XML
+		#[serde(skip_serializing_if = "Vec::is_empty")]
+		pub unknown: Vec<String>,
+	}
+}
+
+impl HrExtension {
+	// Blok s podacima o fiskalizaciji, redom kojim se prikazuje na računu
+	pub fn fiscal_data_block(&self) -> Vec<(&'static str, String)> {
+		let mut rows = Vec::new();
+		let fields = [
+			("operator_code", &self.operator_code),
+			("operator_oib", &self.operator_oib),
+			("business_premises_code", &self.business_premises_code),
+			("device_code", &self.device_code),
+			("issuer_security_code", &self.issuer_security_code),
+			("unique_invoice_identifier", &self.unique_invoice_identifier),
+		];
+		for (key, value) in fields {
+			if let Some(value) = value {
+				rows.push((mapping::field::<Self>(key).label_hr, value.clone()));
+			}
+		}
+		if self.vat_on_payment == Some(true) {
+			rows.push((mapping::field::<Self>("vat_on_payment").label_hr, "Da".to_owned()));
+		}
+
+		rows
+	}
+
+	// Blok s naslovom, po jedan redak za svaki podatak, prazan ako podataka nema
+	pub fn panel_text(&self) -> String {
+		let rows = self.fiscal_data_block();
+		if rows.is_empty() {
+			return String::new();
+		}
+
+		let mut text = "PODACI O FISKALIZACIJI".to_owned();
+		for (label, value) in rows {
+			text.push_str(&std::format!("\n{}: {}", label, value));
+		}
+		text
+	}
+}
+
+// Proširenje koje ne prepoznajemo, čuva se kao izvorni XML
+#[derive(Debug, Default, serde::Serialize)]
+pub struct RawExtension {
+	// /Invoice/ext:UBLExtensions/ext:UBLExtension/ext:ExtensionURI
+	// 0..1
+	pub uri: Option<String>,
+	// /Invoice/ext:UBLExtensions/ext:UBLExtension/ext:ExtensionContent
+	// 1..1
+	pub xml: String,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{ParseErrorKind, ParseOptions, mapping::Mapped};
+
+	const INVOICE: &str = include_str!("../tests/data/invoice.xml");
+
+	#[test]
+	fn hr_extension() {
+		let xml_data = crate::from_str(INVOICE).unwrap();
+		let extension = xml_data.hr_extension.as_ref().unwrap();
+		assert_eq!(extension.operator_oib.as_deref(), Some("94577403194"));
+		assert_eq!(extension.vat_on_payment, Some(false));
+		assert!(extension.unknown.is_empty());
+
+		assert_eq!(xml_data.extensions.len(), 1);
+		assert_eq!(xml_data.extensions[0].uri.as_deref(), Some("urn:example:other"));
+	}
+
+	#[test]
+	fn fields_in_table() {
+		let (path, field) = mapping::fields::<crate::XmlData>("/Invoice")
+			.into_iter()
+			.find(|(_, x)| x.key == "operator_oib")
+			.unwrap();
+		assert_eq!(
+			path,
+			"/Invoice/ext:UBLExtensions/ext:UBLExtension/ext:ExtensionContent/hrextac:HRFISK20Data/hrextac:OperatorOIB"
+		);
+		assert_eq!((field.label_hr, field.cardinality.to_string()), ("OIB operatera", "0..1".to_owned()));
+		assert_eq!(HrExtension::FIELDS.len(), 7);
+	}
+
+	#[test]
+	fn vat_on_payment() {
+		let xml = INVOICE.replace(">false</hrextac:VATOnPayment>", ">1</hrextac:VATOnPayment>");
+		let extension = crate::from_str(&xml).unwrap().hr_extension.unwrap();
+		assert_eq!(extension.vat_on_payment, Some(true));
+
+		let xml = INVOICE.replace(">false</hrextac:VATOnPayment>", ">da</hrextac:VATOnPayment>");
+		let error = crate::from_str(&xml).unwrap_err();
+		assert!(matches!(error.kind, ParseErrorKind::InvalidValue { ref value, .. } if value == "da"), "{:?}", error);
+	}
+
+	#[test]
+	fn panel_text() {
+		let xml = INVOICE.replace(">false</hrextac:VATOnPayment>", ">true</hrextac:VATOnPayment>");
+		let extension = crate::from_str(&xml).unwrap().hr_extension.unwrap();
+		assert_eq!(extension.fiscal_data_block().len(), 7);
+		assert_eq!(
+			extension.panel_text(),
+			"PODACI O FISKALIZACIJI\n\
+			 Oznaka operatera: OPER1\n\
+			 OIB operatera: 94577403194\n\
+			 Oznaka poslovnog prostora: POSL1\n\
+			 Oznaka naplatnog uređaja: 1\n\
+			 Zaštitni kod izdavatelja (ZKI): e4d909c290d0fb1ca068ffaddf22cbd0\n\
+			 Jedinstveni identifikator računa (JIR): a2a2a2a2-1234-5678-9abc-def012345678\n\
+			 Obračun prema naplaćenoj naknadi: Da"
+		);
+		assert_eq!(HrExtension::default().panel_text(), "");
+	}
+
+	// Nepoznati podelement čuva se kao izvorni XML i bez lossless, a s lossless nije i nepoznati element
+	#[test]
+	fn unknown_hr_element_kept() {
+		let xml = INVOICE.replace(
+			"<hrextac:DeviceCode>1</hrextac:DeviceCode>",
+			"<hrextac:DeviceCode>1</hrextac:DeviceCode>\
+			 <hrextac:PaymentDeviceCode a=\"b\">2<hrextac:Part>3</hrextac:Part></hrextac:PaymentDeviceCode>",
+		);
+		for lossless in [false, true] {
+			let options = ParseOptions { lossless, ..Default::default() };
+			let xml_data = crate::parse_invoice_with(xml.as_bytes(), &options).unwrap();
+			let extension = xml_data.hr_extension.unwrap();
+			assert_eq!(extension.device_code.as_deref(), Some("1"));
+			assert_eq!(extension.operator_code.as_deref(), Some("OPER1"));
+			// Izvorni XML nosi i deklaracije prostora imena iz roditeljskih elemenata, kao RawExtension
+			let [raw] = extension.unknown.as_slice() else {
+				panic!("{:?}", extension.unknown)
+			};
+			assert!(raw.starts_with("<hrextac:PaymentDeviceCode "), "{}", raw);
+			assert!(raw.ends_with(r#" a="b">2<hrextac:Part>3</hrextac:Part></hrextac:PaymentDeviceCode>"#), "{}", raw);
+			assert!(!xml_data.unknown.iter().any(|x| x.path.contains("HRFISK20Data")), "{:?}", xml_data.unknown);
+		}
+	}
+}
@@ -0,0 +1,333 @@
+// IBAN i BIC računa za plaćanje
+//
+// IBAN se prihvaća s razmacima ili bez njih i malim ili velikim slovima. Provjeravaju se duljina propisana za
+// državu (registar IBAN, ISO 13616) i kontrolni broj MOD 97. Elektronički oblik je bez razmaka, a oblik za ispis
+// u skupinama po četiri znaka, npr. HR12 1001 0051 8630 0016 0.
+
+use crate::{codelist::CodeList, mapping::Invalid};
+use std::{
+	fmt::{self, Display},
+	str::FromStr,
+};
+
+// Duljina IBAN-a po državi iz registra IBAN
+const LENGTHS: &[(&str, usize)] = &[
+	("AD", 24),
+	("AE", 23),
+	("AL", 28),
+	("AT", 20),
+	("AZ", 28),
+	("BA", 20),
+	("BE", 16),
+	("BG", 22),
+	("BH", 22),
+	("BI", 27),
+	("BR", 29),
+	("BY", 28),
+	("CH", 21),
+	("CR", 22),
+	("CY", 28),
+	("CZ", 24),
+	("DE", 22),
+	("DJ", 27),
+	("DK", 18),
+	("DO", 28),
+	("EE", 20),
+	("EG", 29),
+	("ES", 24),
+	("FI", 18),
+	("FK", 18),
+	("FO", 18),
+	("FR", 27),
+	("GB", 22),
+	("GE", 22),
+	("GI", 23),
+	("GL", 18),
+	("GR", 27),
+	("GT", 28),
+	("HN", 28),
+	("HR", 21),
+	("HU", 28),
+	("IE", 22),
+	("IL", 23),
+	("IQ", 23),
+	("IS", 26),
+	("IT", 27),
+	("JO", 30),
+	("KW", 30),
+	("KZ", 20),
+	("LB", 28),
+	("LC", 32),
+	("LI", 21),
+	("LT", 20),
+	("LU", 20),
+	("LV", 21),
+	("LY", 25),
+	("MC", 27),
+	("MD", 24),
+	("ME", 22),
+	("MK", 19),
+	("MN", 20),
+	("MR", 27),
+	("MT", 31),
+	("MU", 30),
+	("NI", 28),
+	("NL", 18),
+	("NO", 15),
+	("OM", 23),
+	("PK", 24),
+	("PL", 28),
+	("PS", 29),
+	("PT", 25),
+	("QA", 29),
+	("RO", 24),
+	("RS", 22),
+	("RU", 33),
+	("SA", 24),
+	("SC", 31),
+	("SD", 18),
+	("SE", 24),
+	("SI", 19),
+	("SK", 24),
+	("SM", 27),
+	("SO", 23),
+	("ST", 25),
+	("SV", 28),
+	("TL", 23),
+	("TN", 24),
+	("TR", 26),
+	("UA", 29),
+	("VA", 22),
+	("VG", 24),
+	("XK", 20),
+	("YE", 30),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Iban(String);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IbanError {
+	// Znak koji nije slovo, znamenka ili razmak
+	Characters,
+	// Država koja nema IBAN ili nije u registru
+	Country(String),
+	Length { expected: usize, found: usize },
+	Checksum,
+}
+
+impl Display for IbanError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Characters => write!(f, "IBAN smije sadržavati samo slova, znamenke i razmake"),
+			Self::Country(country) => write!(f, "država {} nema IBAN", country),
+			Self::Length { expected, found } => write!(f, "IBAN ima {} znakova, a treba imati {}", found, expected),
+			Self::Checksum => write!(f, "kontrolni broj IBAN-a nije ispravan"),
+		}
+	}
+}
+
+impl std::error::Error for IbanError {}
+
+impl Iban {
+	// Elektronički oblik, bez razmaka
+	pub fn as_str(&self) -> &str {
+		&self.0
+	}
+
+	pub fn country(&self) -> &str {
+		&self.0[..2]
+	}
+
+	// Oblik za ispis, u skupinama po četiri znaka
+	pub fn grouped(&self) -> String {
+		let groups: Vec<&str> = self
+			.0
+			.as_bytes()
+			.chunks(4)
+			.map(|x| std::str::from_utf8(x).unwrap())
+			.collect();
+		groups.join(" ")
+	}
+}
+
+// Ostatak dijeljenja s 97 nakon premještanja prva četiri znaka na kraj, slova se pretvaraju u brojeve 10 do 35
+fn mod97(iban: &str) -> u32 {
+	let (head, tail) = iban.split_at(4);
+	tail.chars().chain(head.chars()).fold(0, |remainder, c| {
+		let value = c.to_digit(36).unwrap();
+		match value {
+			0..10 => (remainder * 10 + value) % 97,
+			_ => (remainder * 100 + value) % 97,
+		}
+	})
+}
+
+impl FromStr for Iban {
+	type Err = IbanError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let iban: String = s
+			.chars()
+			.filter(|x| !x.is_whitespace())
+			.map(|x| x.to_ascii_uppercase())
+			.collect();
+		if iban.len() < 4 || !iban.bytes().all(|x| x.is_ascii_alphanumeric()) {
+			return Err(IbanError::Characters);
+		}
+
+		let country = &iban[..2];
+		let expected = LENGTHS
+			.iter()
+			.find(|(code, _)| *code == country)
+			.map(|(_, length)| *length)
+			.ok_or_else(|| IbanError::Country(country.to_owned()))?;
+		if iban.len() != expected {
+			return Err(IbanError::Length { expected, found: iban.len() });
+		}
+		if !iban[2..4].bytes().all(|x| x.is_ascii_digit()) || mod97(&iban) != 1 {
+			return Err(IbanError::Checksum);
+		}
+
+		Ok(Self(iban))
+	}
+}
+
+// Ispisuje se u obliku za ispis, elektronički oblik daje as_str
+impl Display for Iban {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(&self.grouped())
+	}
+}
+
+impl serde::Serialize for Iban {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(&self.0)
+	}
+}
+
+// BIC (ISO 9362): oznaka banke, država, mjesto i neobavezna oznaka podružnice, npr. ZABAHR2X ili ZABAHR2XXXX
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Bic(String);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BicError {
+	Length(usize),
+	Format,
+	Country(String),
+}
+
+impl Display for BicError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Length(length) => write!(f, "BIC ima 8 ili 11 znakova, a ne {}", length),
+			Self::Format => write!(f, "BIC nije ispravnog oblika"),
+			Self::Country(country) => write!(f, "nepoznata država {} u BIC-u", country),
+		}
+	}
+}
+
+impl std::error::Error for BicError {}
+
+impl Bic {
+	pub fn as_str(&self) -> &str {
+		&self.0
+	}
+
+	pub fn country(&self) -> &str {
+		&self.0[4..6]
+	}
+}
+
+impl FromStr for Bic {
+	type Err = BicError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let bic = s.trim().to_ascii_uppercase();
+		if bic.len() != 8 && bic.len() != 11 {
+			return Err(BicError::Length(bic.len()));
+		}
+
+		let bytes = bic.as_bytes();
+		if !bytes[..6].iter().all(u8::is_ascii_alphabetic) || !bytes[6..].iter().all(u8::is_ascii_alphanumeric) {
+			return Err(BicError::Format);
+		}
+		if CodeList::Country.get(&bic[4..6]).is_none() {
+			return Err(BicError::Country(bic[4..6].to_owned()));
+		}
+
+		Ok(Self(bic))
+	}
+}
+
+impl Display for Bic {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(&self.0)
+	}
+}
+
+impl serde::Serialize for Bic {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(&self.0)
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AccountError {
+	Iban(IbanError),
+	Bic(BicError),
+}
+
+impl Display for AccountError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Iban(e) => e.fmt(f),
+			Self::Bic(e) => e.fmt(f),
+		}
+	}
+}
+
+// Račun za plaćanje s neispravnim IBAN-om ili BIC-om
+pub type InvalidAccount = Invalid<AccountError>;
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn valid_iban() {
+		for text in [
+			"HR1210010051863000160",
+			"hr12 1001 0051 8630 0016 0",
+			"DE89370400440532013000",
+			"GB82WEST12345698765432",
+			"NO9386011117947",
+		] {
+			assert!(text.parse::<Iban>().is_ok(), "{}", text);
+		}
+		let iban: Iban = "hr12 1001 0051 8630 0016 0".parse().unwrap();
+		assert_eq!(iban.as_str(), "HR1210010051863000160");
+		assert_eq!(iban.to_string(), "HR12 1001 0051 8630 0016 0");
+		assert_eq!(iban.country(), "HR");
+	}
+
+	#[test]
+	fn invalid_iban() {
+		assert_eq!("HR1210010051863000161".parse::<Iban>(), Err(IbanError::Checksum));
+		assert_eq!("HRAB10010051863000160".parse::<Iban>(), Err(IbanError::Checksum));
+		assert_eq!("HR121001005186300016".parse::<Iban>(), Err(IbanError::Length { expected: 21, found: 20 }));
+		assert_eq!("US12100100518630".parse::<Iban>(), Err(IbanError::Country("US".to_owned())));
+		assert_eq!("HR12-1001-0051-8630-0016-0".parse::<Iban>(), Err(IbanError::Characters));
+		assert_eq!("HRČ1210010051863000160".parse::<Iban>(), Err(IbanError::Characters));
+		assert_eq!("HR1".parse::<Iban>(), Err(IbanError::Characters));
+	}
+
+	#[test]
+	fn bic() {
+		assert_eq!("zabahr2x".parse::<Bic>().unwrap().as_str(), "ZABAHR2X");
+		assert_eq!("ZABAHR2XXXX".parse::<Bic>().unwrap().country(), "HR");
+		assert_eq!("ZABAHR2".parse::<Bic>(), Err(BicError::Length(7)));
+		assert_eq!("ZAB1HR2X".parse::<Bic>(), Err(BicError::Format));
+		assert_eq!("ZABAQQ2X".parse::<Bic>(), Err(BicError::Country("QQ".to_owned())));
+	}
+}
@@ -0,0 +1,841 @@
+use crate::{
+	codelist::CodeLabel,
+	date::Date,
+	decimal::{Amount, CurrencyError, Quantity},
+	extensions::{HrExtension, RawExtension},
+	iban::{AccountError, Bic, BicError, Iban, IbanError, InvalidAccount},
+	kpd::{self, KpdError, KpdTable},
+	language::Language,
+	mapping::{self, Cardinality, Field, Invalid, Mapped, Missing, Whitespace, mapped},
+	oib::{self, InvalidOib},
+	reference::{PaymentReference, ReferenceError},
+	signature::SignatureInfo,
+	specification::{BusinessProcess, Specification},
+	words,
+};
+
+mapped! {
+	#[derive(Default, Debug, serde::Serialize)]
+	pub struct XmlData {
+		#[bt("BT-24", "cbc:CustomizationID", 1..1, "Identifikator specifikacije", "Specification identifier")]
+		pub customization_id: String,
+		#[bt("BT-23", "cbc:ProfileID", 0..1, "Vrsta poslovnog procesa", "Business process type")]
+		pub profile_id: Option<String>,
+		#[bt("BT-1", "cbc:ID", 1..1, "Broj računa", "Invoice number")]
+		pub id: String,
+		#[bt("BT-2", "cbc:IssueDate", 1..1, "Datum izdavanja računa", "Invoice issue date")]
+		pub issue_date: Option<Date>,
+		#[bt("BT-3", "cbc:InvoiceTypeCode", 1..1, "Šifra vrste računa", "Invoice type code")]
+		pub invoice_type_code: String,
+		#[bt("BT-5", "cbc:DocumentCurrencyCode", 1..1, "Šifra valute računa", "Invoice currency code")]
+		pub document_currency_code: String,
+		#[bt("BT-6", "cbc:TaxCurrencyCode", 0..1, "Šifra valute obračunatog PDV-a", "VAT accounting currency code")]
+		pub tax_currency_code: Option<String>,
+		#[bt(
+			"BT-7",
+			"cbc:TaxPointDate",
+			0..1,
+			"Datum na koji porez na dodanu vrijednost postaje važeći",
+			"Value added tax point date"
+		)]
+		pub tax_point_date: Option<Date>,
+		#[bt(
+			"BT-8",
+			"cbc:DescriptionCode",
+			0..1,
+			"Šifra datuma na koji porez na dodanu vrijednost postaje važeći",
+			"Value added tax point date code"
+		)]
+		pub description_code: Option<String>,
+		#[bt("BT-9", "cbc:DueDate", 0..1, "Datum dospijeća plaćanja", "Payment due date")]
+		pub due_date: Option<Date>,
+		#[bt("BT-10", "cbc:BuyerReference", 0..1, "Referenca na kupca", "Buyer reference")]
+		pub buyer_reference: Option<String>,
+		#[bt("BT-19", "cbc:AccountingCost", 0..1, "Mjesto troška", "Buyer accounting reference")]
+		pub accounting_cost: Option<String>,
+		#[bt(
+			"BT-73",
+			"cac:InvoicePeriod/cbc:StartDate",
+			0..1,
+			"Datum početka obračunskog razdoblja",
+			"Invoicing period start date"
+		)]
+		pub invoice_period_state_date: Option<Date>,
+		#[bt(
+			"BT-74",
+			"cac:InvoicePeriod/cbc:EndDate",
+			0..1,
+			"Datum završetka obračunskog razdoblja",
+			"Invoicing period end date"
+		)]
+		pub invoice_period_end_date: Option<Date>,
+		#[bt("BT-22", "cbc:Note", 0..n, "Napomena uz račun", "Invoice note", PreserveLines)]
+		pub notes: Vec<String>,
+		#[bt("", "cac:TaxTotal", 1..2, "UKUPNI IZNOS PDV-a", "VAT TOTAL")]
+		pub tax_totals: Vec<TaxTotal>,
+		#[bt("BG-22", "cac:LegalMonetaryTotal", 1..1, "UKUPNI IZNOSI", "DOCUMENT TOTALS")]
+		pub legal_monetary_total: LegalMonetaryTotal,
+		#[bt("BG-16", "cac:PaymentMeans", 0..1, "UPUTE ZA PLAĆANJE", "PAYMENT INSTRUCTIONS")]
+		pub payment_means: Option<PaymentMeans>,
+		#[bt("BG-4", "cac:AccountingSupplierParty", 1..1, "PRODAVATELJ", "SELLER")]
+		pub accounting_supplier_party: AccountingSupplierParty,
+		#[bt("BG-7", "cac:AccountingCustomerParty", 1..1, "KUPAC", "BUYER")]
+		pub accounting_customer_party: AccountingCustomerParty,
+		#[bt("BG-25", "cac:InvoiceLine", 1..n, "STAVKA RAČUNA", "INVOICE LINE")]
+		pub invoice_lines: Vec<InvoiceLine>,
+		#[bt(
+			"",
+			"ext:UBLExtensions/ext:UBLExtension/ext:ExtensionContent/hrextac:HRFISK20Data",
+			0..1,
+			"HRVATSKO PROŠIRENJE ZA FISKALIZACIJU",
+			"CROATIAN FISCALISATION EXTENSION"
+		)]
+		pub hr_extension: Option<HrExtension>,
+	}
+	extra {
+		// OSTALA PROŠIRENJA
+		// /Invoice/ext:UBLExtensions/ext:UBLExtension
+		// 0..n
+		pub extensions: Vec<RawExtension>,
+		// ELEKTRONIČKI POTPIS
+		// /Invoice/ext:UBLExtensions/ext:UBLExtension/ext:ExtensionContent/sig:UBLDocumentSignatures/sac:SignatureInformation/ds:Signature
+		// 0..1
+		pub signature: Option<SignatureInfo>,
+		// NEPOZNATI ELEMENTI I ATRIBUTI
+		// Samo uz ParseOptions::lossless, redom kojim se pojavljuju u dokumentu
+		#[serde(skip_serializing_if = "Vec::is_empty")]
+		pub unknown: Vec<UnknownElement>,
+	}
+}
+
+impl XmlData {
+	pub fn specification(&self) -> Specification {
+		Specification::parse(&self.customization_id)
+	}
+
+	pub fn business_process(&self) -> Option<BusinessProcess> {
+		self.profile_id.as_deref().and_then(BusinessProcess::parse)
+	}
+
+	// Obavezna polja koja nedostaju u dokumentu
+	pub fn missing_fields(&self) -> Vec<Missing> {
+		let mut out = Vec::new();
+		Mapped::missing(self, "/Invoice", &mut out);
+		// Iznos PDV-a drugog TaxTotal je BT-111, a ne BT-110 iz tablice polja
+		for (i, tax_total) in self.tax_totals.iter().enumerate() {
+			let path = std::format!("/Invoice/cac:TaxTotal[{}]/cbc:TaxAmount", i + 1);
+			for missing in out.iter_mut().filter(|x| x.path == path) {
+				missing.field = tax_total.tax_amount_field(&self.document_currency_code);
+			}
+		}
+		out
+	}
+
+	// Šifre iz popisa u poljima zaglavlja, s nazivima
+	pub fn code_labels(&self) -> Vec<CodeLabel> {
+		let mut out = Vec::new();
+		Mapped::codes(self, "/Invoice", &mut out);
+		out
+	}
+
+	// Polja stranaka s OIB-om koji nije ispravan
+	pub fn invalid_oibs(&self) -> Vec<InvalidOib> {
+		let mut out = Vec::new();
+		self.accounting_supplier_party
+			.invalid_oibs("/Invoice/cac:AccountingSupplierParty", &mut out);
+		self.accounting_customer_party
+			.invalid_oibs("/Invoice/cac:AccountingCustomerParty", &mut out);
+		out
+	}
+
+	// Računi za plaćanje s neispravnim IBAN-om ili BIC-om
+	pub fn invalid_accounts(&self) -> Vec<InvalidAccount> {
+		let mut out = Vec::new();
+		if let Some(payment_means) = &self.payment_means {
+			let sepa = payment_means.payment_means_code.trim() == "58";
+			for (i, account) in payment_means.payee_financial_accounts.iter().enumerate() {
+				let parent = std::format!("/Invoice/cac:PaymentMeans/cac:PayeeFinancialAccount[{}]", i + 1);
+				account.invalid_account(&parent, sepa, &mut out);
+			}
+		}
+		out
+	}
+
+	// Poziv na broj koji ne odgovara pravilima svog modela, ili čiji se model ne provjerava u potpunosti
+	pub fn invalid_payment_reference(&self) -> Option<Invalid<ReferenceError>> {
+		let payment_means = self.payment_means.as_ref()?;
+		let error = match payment_means.payment_reference()? {
+			Ok(reference) => reference.unchecked()?,
+			Err(e) => e,
+		};
+		Some(Invalid::new::<PaymentMeans>(
+			"/Invoice/cac:PaymentMeans",
+			"payment_id",
+			payment_means.payment_id.as_deref().unwrap_or_default(),
+			error,
+		))
+	}
+
+	// Ukupni iznos PDV-a u valuti računa (BT-110)
+	pub fn tax_total(&self) -> Option<&Amount> {
+		self.tax_total_in(&self.document_currency_code)
+	}
+
+	// Ukupni iznos PDV-a u valuti obračuna PDV-a (BT-111), ako se ona razlikuje od valute računa
+	pub fn tax_total_in_tax_currency(&self) -> Option<&Amount> {
+		let currency = self.tax_currency_code.as_deref()?.trim();
+		if currency == self.document_currency_code.trim() {
+			return None;
+		}
+		self.tax_total_in(currency)
+	}
+
+	fn tax_total_in(&self, currency: &str) -> Option<&Amount> {
+		self.tax_totals
+			.iter()
+			.find(|x| x.tax_amount_currency_id.trim() == currency.trim())
+			.and_then(|x| x.tax_amount.as_ref())
+	}
+
+	// Iznosi koji nisu u valuti računa, ukupni iznosi PDV-a u valuti koja nije ni valuta računa ni valuta obračuna
+	// PDV-a, te ukupni iznos PDV-a koji nedostaje za valutu obračuna PDV-a
+	pub fn currency_mismatches(&self) -> Vec<Invalid<CurrencyError>> {
+		let document = self.document_currency_code.trim();
+		let tax = self
+			.tax_currency_code
+			.as_deref()
+			.map(str::trim)
+			.filter(|x| *x != document);
+		let mut out = Vec::new();
+
+		let total = &self.legal_monetary_total;
+		let parent = "/Invoice/cac:LegalMonetaryTotal";
+		for (key, amount) in [
+			("line_extension_amount", &total.line_extension_amount),
+			("tax_exclusive_amount", &total.tax_exclusive_amount),
+			("tax_inclusive_amount", &total.tax_inclusive_amount),
+			("payable_amount", &total.payable_amount),
+		] {
+			if let Some(currency) = amount.as_ref().and_then(|x| x.currency.as_deref())
+				&& currency.trim() != document
+			{
+				let error = CurrencyError::NotDocumentCurrency(document.to_owned());
+				out.push(Invalid::new::<LegalMonetaryTotal>(parent, key, currency, error));
+			}
+		}
+
+		for (i, tax_total) in self.tax_totals.iter().enumerate() {
+			let currency = tax_total.tax_amount_currency_id.trim();
+			if currency != document && Some(currency) != tax {
+				let parent = std::format!("/Invoice/cac:TaxTotal[{}]", i + 1);
+				let error = CurrencyError::NotDocumentCurrency(document.to_owned());
+				out.push(Invalid::new::<TaxTotal>(&parent, "tax_amount_currency_id", currency, error));
+			}
+		}
+
+		if let Some(tax) = tax
+			&& self.tax_total_in(tax).is_none()
+		{
+			out.push(Invalid::new::<Self>("/Invoice", "tax_currency_code", tax, CurrencyError::MissingTaxTotal));
+		}
+
+		out
+	}
+
+	pub fn signature(&mut self) -> &mut SignatureInfo {
+		self.signature.get_or_insert_with(SignatureInfo::default)
+	}
+}
+
+// Element koji nije u tablici polja, ili poznati element s atributima koji nisu u tablici
+#[derive(Debug, Default, serde::Serialize)]
+pub struct UnknownElement {
+	pub path: String,
+	pub attributes: Vec<(String, String)>,
+	// Tekst samog elementa, bez teksta podelemenata
+	pub text: String,
+}
+
+mapped! {
+	#[derive(Debug, Default, serde::Serialize)]
+	pub struct PostalAddressCustomer {
+		#[bt("BT-50", "cbc:StreetName", 0..1, "Redak adrese Kupca 1", "Buyer address line 1")]
+		pub street_name: Option<String>,
+		#[bt("BT-51", "cbc:AdditionalStreetName", 0..1, "Redak adrese Kupca 2", "Buyer address line 2")]
+		pub additional_street_name: Option<String>,
+		#[bt("BT-163", "cac:AddressLine/cbc:Line", 0..1, "Redak adrese Kupca 3", "Buyer address line 3")]
+		pub line: Option<String>,
+		#[bt("BT-52", "cbc:CityName", 0..1, "Grad Kupca", "Buyer city")]
+		pub city_name: Option<String>,
+		#[bt("BT-53", "cbc:PostalZone", 0..1, "Poštanski broj Kupca", "Buyer post code")]
+		pub postal_zone: Option<String>,
+		#[bt("BT-54", "cbc:CountrySubentity", 0..1, "Županija Kupca", "Buyer country subdivision")]
+		pub country_subentity: Option<String>,
+		#[bt("BT-55", "cac:Country/cbc:IdentificationCode", 1..1, "Šifra države Kupca", "Buyer country code")]
+		pub identification_code: String,
+	}
+}
+
+mapped! {
+	#[derive(Debug, Default, serde::Serialize)]
+	pub struct AccountingCustomerParty {
+		#[bt("BT-44", "cac:Party/cac:PartyLegalEntity/cbc:RegistrationName", 1..1, "Ime Kupca", "Buyer name")]
+		pub registration_name: String,
+		#[bt("BT-45", "cac:Party/cac:PartyName/cbc:Name", 0..1, "Trgovački naziv Kupca", "Buyer trading name")]
+		pub name: Option<String>,
+		#[bt("BT-46", "cac:Party/cac:PartyIdentification/cbc:ID", 0..1, "Identifikator Kupca", "Buyer identifier")]
+		pub id: Option<String>,
+		#[bt(
+			"BT-46",
+			"cac:Party/cac:PartyIdentification/cbc:ID/@schemeID",
+			0..1,
+			"Identifikator sheme",
+			"Scheme identifier"
+		)]
+		pub id_scheme_id: Option<String>,
+		#[bt(
+			"BT-47",
+			"cac:Party/cac:PartyLegalEntity/cbc:CompanyID",
+			0..1,
+			"Identifikator pravne registracije Kupca",
+			"Buyer legal registration identifier"
+		)]
+		pub legal_entity_company_id: Option<String>,
+		#[bt(
+			"BT-47",
+			"cac:Party/cac:PartyLegalEntity/cbc:CompanyID/@schemeID",
+			0..1,
+			"Identifikator sheme",
+			"Scheme identifier"
+		)]
+		pub legal_entity_company_id_scheme_id: Option<String>,
+		#[bt(
+			"BT-48",
+			"cac:Party/cac:PartyTaxScheme/cbc:CompanyID",
+			0..1,
+			"Porezni identifikator Kupca",
+			"Buyer VAT identifier"
+		)]
+		pub tax_scheme_company_id: Option<String>,
+		#[bt("BT-49", "cac:Party/cbc:EndpointID", 0..1, "Elektronička adresa Kupca", "Buyer electronic address")]
+		pub endpoint_id: Option<String>,
+		#[bt("BT-49", "cac:Party/cbc:EndpointID/@schemeID", 1..1, "Identifikator sheme", "Scheme identifier")]
+		pub endpoint_id_scheme_id: Option<String>,
+		#[bt("BG-8", "cac:Party/cac:PostalAddress", 1..1, "POŠTANSKA ADRESA KUPCA", "BUYER POSTAL ADDRESS")]
+		pub postal_address: PostalAddressCustomer,
+	}
+}
+
+impl AccountingCustomerParty {
+	// OIB je elektronička adresa sa shemom 9934, porezni broj s prefiksom HR i registracijski broj hrvatskog Kupca
+	fn invalid_oibs(&self, parent: &str, out: &mut Vec<InvalidOib>) {
+		let croatian = self.postal_address.identification_code == "HR"
+			|| self.legal_entity_company_id_scheme_id.as_deref() == Some("9934");
+		let endpoint_id = self
+			.endpoint_id
+			.as_deref()
+			.filter(|_| self.endpoint_id_scheme_id.as_deref() == Some("9934"));
+		let tax_scheme_company_id = self
+			.tax_scheme_company_id
+			.as_deref()
+			.filter(|x| x.trim().starts_with("HR"));
+		let legal_entity_company_id = self.legal_entity_company_id.as_deref().filter(|_| croatian);
+
+		oib::check::<Self>(parent, "endpoint_id", endpoint_id, out);
+		oib::check::<Self>(parent, "legal_entity_company_id", legal_entity_company_id, out);
+		oib::check::<Self>(parent, "tax_scheme_company_id", tax_scheme_company_id, out);
+	}
+}
+
+mapped! {
+	#[derive(Debug, Default, serde::Serialize)]
+	pub struct PostalAddressSupplier {
+		#[bt("BT-35", "cbc:StreetName", 0..1, "Redak adrese Prodavatelja 1", "Seller address line 1")]
+		pub street_name: Option<String>,
+		#[bt("BT-36", "cbc:AdditionalStreetName", 0..1, "Redak adrese Prodavatelja 2", "Seller address line 2")]
+		pub additional_street_name: Option<String>,
+		#[bt("BT-162", "cac:AddressLine/cbc:Line", 0..1, "Redak adrese Prodavatelja 3", "Seller address line 3")]
+		pub line: Option<String>,
+		#[bt("BT-37", "cbc:CityName", 0..1, "Grad Prodavatelja", "Seller city")]
+		pub city_name: Option<String>,
+		#[bt("BT-38", "cbc:PostalZone", 0..1, "Poštanski broj Prodavatelja", "Seller post code")]
+		pub postal_zone: Option<String>,
+		#[bt("BT-39", "cbc:CountrySubentity", 0..1, "Županija Prodavatelja", "Seller country subdivision")]
+		pub country_subentity: Option<String>,
+		#[bt("BT-40", "cac:Country/cbc:IdentificationCode", 1..1, "Šifra države Prodavatelja", "Seller country code")]
+		pub identification_code: String,
+	}
+}
+
+mapped! {
+	#[derive(Debug, Default, serde::Serialize)]
+	pub struct AccountingSupplierParty {
+		#[bt("BT-27", "cac:Party/cac:PartyLegalEntity/cbc:RegistrationName", 1..1, "Naziv Prodavatelja", "Seller name")]
+		pub registration_name: String,
+		#[bt(
+			"BT-33",
+			"cac:Party/cac:PartyLegalEntity/cbc:CompanyLegalForm",
+			1..1,
+			"Dodatne pravne informacije o Prodavatelju",
+			"Seller additional legal information"
+		)]
+		pub company_legal_form: String,
+		#[bt("BT-34", "cac:Party/cbc:EndpointID", 1..1, "Elektronička adresa Prodavatelja", "Seller electronic address")]
+		pub endpoint_id: String,
+		#[bt("BT-34", "cac:Party/cbc:EndpointID/@schemeID", 1..1, "Identifikator sheme", "Scheme identifier")]
+		pub endpoint_id_scheme_id: String,
+		#[bt(
+			"BT-30",
+			"cac:Party/cac:PartyLegalEntity/cbc:CompanyID",
+			0..1,
+			"Identifikator pravne registracije Prodavatelja",
+			"Seller legal registration identifier"
+		)]
+		pub legal_entity_company_id: Option<String>,
+		#[bt(
+			"BT-30",
+			"cac:Party/cac:PartyLegalEntity/cbc:CompanyID/@schemeID",
+			0..1,
+			"Identifikator sheme",
+			"Scheme identifier"
+		)]
+		pub legal_entity_company_id_scheme_id: Option<String>,
+		#[bt(
+			"BT-31",
+			"cac:Party/cac:PartyTaxScheme/cbc:CompanyID",
+			0..1,
+			"Porezni identifikator Prodavatelja",
+			"Seller VAT identifier"
+		)]
+		pub tax_scheme_company_id: Option<String>,
+		#[bt("BG-5", "cac:Party/cac:PostalAddress", 1..1, "POŠTANSKA ADRESA PRODAVATELJA", "SELLER POSTAL ADDRESS")]
+		pub postal_address: PostalAddressSupplier,
+	}
+}
+
+impl AccountingSupplierParty {
+	// Ista pravila kao za Kupca
+	fn invalid_oibs(&self, parent: &str, out: &mut Vec<InvalidOib>) {
+		let croatian = self.postal_address.identification_code == "HR"
+			|| self.legal_entity_company_id_scheme_id.as_deref() == Some("9934");
+		let endpoint_id = Some(self.endpoint_id.as_str()).filter(|_| self.endpoint_id_scheme_id == "9934");
+		let tax_scheme_company_id = self
+			.tax_scheme_company_id
+			.as_deref()
+			.filter(|x| x.trim().starts_with("HR"));
+		let legal_entity_company_id = self.legal_entity_company_id.as_deref().filter(|_| croatian);
+
+		oib::check::<Self>(parent, "endpoint_id", endpoint_id, out);
+		oib::check::<Self>(parent, "legal_entity_company_id", legal_entity_company_id, out);
+		oib::check::<Self>(parent, "tax_scheme_company_id", tax_scheme_company_id, out);
+	}
+}
+
+mapped! {
+	#[derive(Debug, Default, serde::Serialize)]
+	pub struct PayeeFinancialAccount {
+		#[bt("BT-84", "cbc:ID", 1..1, "Identifikator računa plaćanja", "Payment account identifier")]
+		pub id: String,
+		#[bt(
+			"BT-86",
+			"cac:FinancialInstitutionBranch/cbc:ID",
+			0..1,
+			"Identifikator pružatelja platnih usluga",
+			"Payment service provider identifier"
+		)]
+		pub financial_institution_branch_id: Option<String>,
+	}
+}
+
+impl PayeeFinancialAccount {
+	// IBAN računa, npr. za crtični kod za plaćanje koji se ne smije izraditi s neispravnim IBAN-om
+	pub fn iban(&self) -> Result<Iban, IbanError> {
+		self.id.parse()
+	}
+
+	pub fn bic(&self) -> Option<Result<Bic, BicError>> {
+		self.financial_institution_branch_id.as_deref().map(str::parse)
+	}
+
+	// Identifikator računa provjerava se kao IBAN ako počinje oznakom države ili je plaćanje SEPA kreditnim
+	// transferom (58), jer inače može biti i domaći broj računa
+	fn invalid_account(&self, parent: &str, sepa: bool, out: &mut Vec<InvalidAccount>) {
+		let id = self.id.trim();
+		let iban = sepa || id.get(..2).is_some_and(|x| x.bytes().all(|x| x.is_ascii_alphabetic()));
+		if !id.is_empty()
+			&& iban && let Err(e) = self.iban()
+		{
+			out.push(Invalid::new::<Self>(parent, "id", &self.id, AccountError::Iban(e)));
+		}
+		if let Some(id) = &self.financial_institution_branch_id
+			&& let Some(Err(e)) = self.bic()
+		{
+			out.push(Invalid::new::<Self>(parent, "financial_institution_branch_id", id, AccountError::Bic(e)));
+		}
+	}
+}
+
+mapped! {
+	#[derive(Debug, Default, serde::Serialize)]
+	pub struct PaymentMeans {
+		#[bt("BT-81", "cbc:PaymentMeansCode", 1..1, "Šifra načina plaćanja", "Payment means type code")]
+		pub payment_means_code: String,
+		#[bt("BT-82", "cbc:InstructionNote", 0..1, "Tekst načina plaćanja", "Payment means text")]
+		pub instruction_note: Option<String>,
+		#[bt("BT-83", "cbc:PaymentID", 0..1, "Informacije o doznaci", "Remittance information")]
+		pub payment_id: Option<String>,
+		#[bt("BG-17", "cac:PayeeFinancialAccount", 0..n, "KREDITNI TRANSFER", "CREDIT TRANSFER")]
+		pub payee_financial_accounts: Vec<PayeeFinancialAccount>,
+	}
+}
+
+impl PaymentMeans {
+	// Poziv na broj po modelu HUB-a, None ako BT-83 nije naveden ili ne počinje s HR
+	pub fn payment_reference(&self) -> Option<Result<PaymentReference, ReferenceError>> {
+		self.payment_id
+			.as_deref()
+			.filter(|x| x.trim().starts_with("HR"))
+			.map(str::parse)
+	}
+}
+
+impl LegalMonetaryTotal {
+	// Iznos koji dospijeva na plaćanje slovima, kao neobavezni redak ispod ukupnih iznosa
+	pub fn payable_amount_in_words(&self, document_currency: &str, language: Language) -> Option<String> {
+		words::amount_in_words(self.payable_amount.as_ref()?, document_currency, language)
+	}
+}
+
+// Iznos PDV-a u valuti računa (BT-110) ili, ako se razlikuje, u valuti obračuna PDV-a (BT-111)
+// Oba su ista UBL putanja, pa tablica polja navodi BT-110, a BT-111 je zasebno polje za prikaz i greške
+mapped! {
+	#[derive(Debug, Default, serde::Serialize)]
+	pub struct TaxTotal {
+		#[bt("BT-110", "cbc:TaxAmount", 1..1, "Ukupni iznos PDV-a računa", "Invoice total VAT amount")]
+		pub tax_amount: Option<Amount>,
+		#[bt("", "cbc:TaxAmount/@currencyID", 1..1, "Šifra valute", "Currency code")]
+		pub tax_amount_currency_id: String,
+	}
+}
+
+static TAX_AMOUNT_IN_TAX_CURRENCY: Field = Field {
+	id: "BT-111",
+	path: "cbc:TaxAmount",
+	cardinality: Cardinality { min: 0, max: Some(1) },
+	label_hr: "Ukupni iznos PDV-a u valuti obračuna PDV-a",
+	label_en: "Invoice total VAT amount in accounting currency",
+	whitespace: Whitespace::Collapse,
+	key: "tax_amount",
+	children: &[],
+	attributes: &["currencyID"],
+};
+
+impl TaxTotal {
+	// BT-110 za iznos u valuti računa ili bez valute, inače BT-111
+	pub fn tax_amount_field(&self, document_currency: &str) -> &'static Field {
+		match self.tax_amount_currency_id.trim() {
+			currency if currency.is_empty() || currency == document_currency.trim() => {
+				mapping::field::<Self>("tax_amount")
+			},
+			_ => &TAX_AMOUNT_IN_TAX_CURRENCY,
+		}
+	}
+}
+
+mapped! {
+	#[derive(Debug, Default, serde::Serialize)]
+	pub struct LegalMonetaryTotal {
+		#[bt(
+			"BT-106",
+			"cbc:LineExtensionAmount",
+			1..1,
+			"Zbroj svih neto iznosa stavki računa",
+			"Sum of Invoice line net amount"
+		)]
+		pub line_extension_amount: Option<Amount>,
+		#[bt("BT-109", "cbc:TaxExclusiveAmount", 1..1, "Ukupni iznos računa bez PDV-a", "Invoice total amount without VAT")]
+		pub tax_exclusive_amount: Option<Amount>,
+		#[bt("BT-112", "cbc:TaxInclusiveAmount", 1..1, "Ukupni iznos računa s PDV-om", "Invoice total amount with VAT")]
+		pub tax_inclusive_amount: Option<Amount>,
+		#[bt("BT-115", "cbc:PayableAmount", 1..1, "Iznos koji dospijeva na plaćanje", "Amount due for payment")]
+		pub payable_amount: Option<Amount>,
+	}
+}
+
+mapped! {
+	#[derive(Debug, Default, serde::Serialize)]
+	pub struct InvoiceLine {
+		#[bt("BT-126", "cbc:ID", 1..1, "Identifikator stavke računa", "Invoice line identifier")]
+		pub id: String,
+		#[bt("BT-129", "cbc:InvoicedQuantity", 1..1, "Obračunata količina", "Invoiced quantity")]
+		pub invoiced_quantity: Option<Quantity>,
+		#[bt(
+			"BT-130",
+			"cbc:InvoicedQuantity/@unitCode",
+			1..1,
+			"Šifra jedinica mjere obračunate količine",
+			"Invoiced quantity unit of measure code"
+		)]
+		pub invoiced_quantity_unit_code: String,
+		#[bt("BT-131", "cbc:LineExtensionAmount", 1..1, "Neto iznos stavke računa", "Invoice line net amount")]
+		pub line_extension_amount: Option<Amount>,
+		#[bt("BG-29", "cac:Price", 1..1, "DETALJI O CIJENI", "PRICE DETAILS")]
+		pub price: Price,
+		#[bt("BG-31", "cac:Item", 1..1, "INFORMACIJE O ARTIKLU", "ITEM INFORMATION")]
+		pub item: Item,
+	}
+}
+
+impl InvoiceLine {
+	// Obavezna polja koja nedostaju u stavki, redni broj počinje od 1 kao u XmlData::missing_fields
+	pub fn missing_fields(&self, index: usize) -> Vec<Missing> {
+		let mut out = Vec::new();
+		Mapped::missing(self, &std::format!("/Invoice/cac:InvoiceLine[{}]", index), &mut out);
+		out
+	}
+
+	// Šifre iz popisa u poljima stavke, redni broj kao u missing_fields
+	pub fn code_labels(&self, index: usize) -> Vec<CodeLabel> {
+		let mut out = Vec::new();
+		Mapped::codes(self, &std::format!("/Invoice/cac:InvoiceLine[{}]", index), &mut out);
+		out
+	}
+
+	// KPD šifra koja nije oblika NN.NN.NN ili čiji odjeljak ne postoji, provjerava se i bez pune klasifikacije
+	pub fn invalid_kpd(&self, index: usize) -> Option<Invalid<KpdError>> {
+		let mut classifications = self.item.commodity_classifications.iter().enumerate();
+		let (i, classification) = classifications.find(|(_, x)| x.list_id.as_deref() == Some(kpd::KPD_LIST_ID))?;
+		let error = kpd::check(&classification.code).err()?;
+		let parent =
+			std::format!("/Invoice/cac:InvoiceLine[{}]/cac:Item/cac:CommodityClassification[{}]", index, i + 1);
+		Some(Invalid::new::<CommodityClassification>(&parent, "code", &classification.code, error))
+	}
+}
+
+mapped! {
+	#[derive(Debug, Default, serde::Serialize)]
+	pub struct Price {
+		#[bt("BT-146", "cbc:PriceAmount", 1..1, "Neto cijena artikla", "Item net price")]
+		pub price_amount: Option<Amount>,
+		#[bt("BT-149", "cbc:BaseQuantity", 0..1, "Jedinična količina cijene artikla", "Item price base quantity")]
+		pub base_quantity: Option<Quantity>,
+		#[bt(
+			"BT-150",
+			"cbc:BaseQuantity/@unitCode",
+			0..1,
+			"Šifra jedinice mjere jedinične količine artikla",
+			"Item price base quantity unit of measure code"
+		)]
+		pub base_quantity_unit_code: Option<String>,
+	}
+}
+
+mapped! {
+	#[derive(Debug, Default, serde::Serialize)]
+	pub struct Item {
+		#[bt("BT-153", "cbc:Name", 1..1, "Naziv artikla", "Item name")]
+		pub name: String,
+		#[bt("BT-154", "cbc:Description", 0..1, "Opis artikla", "Item description", PreserveLines)]
+		pub description: Option<String>,
+		#[bt(
+			"BT-158",
+			"cac:CommodityClassification",
+			0..n,
+			"Identifikator klasifikacije artikla",
+			"Item classification identifier"
+		)]
+		pub commodity_classifications: Vec<CommodityClassification>,
+		#[bt(
+			"BG-30",
+			"cac:ClassifiedTaxCategory",
+			1..1,
+			"INFORMACIJA O PDV-u STAVKE RAČUNA",
+			"LINE VAT INFORMATION"
+		)]
+		pub classified_tax_category: ClassifiedTaxCategory,
+	}
+}
+
+impl Item {
+	// KPD šifra, ako je navedena i ispravnog oblika
+	pub fn kpd(&self) -> Result<&str, KpdError> {
+		let classification = self
+			.commodity_classifications
+			.iter()
+			.find(|x| x.list_id.as_deref() == Some(kpd::KPD_LIST_ID))
+			.ok_or(KpdError::Missing)?;
+
+		kpd::check(&classification.code)
+	}
+
+	// KPD šifra i njezin naziv iz pune klasifikacije
+	pub fn kpd_description<'a>(&'a self, table: &'a KpdTable) -> Result<(&'a str, &'a str), KpdError> {
+		let code = self.kpd()?;
+		Ok((code, table.describe(code)?))
+	}
+}
+
+mapped! {
+	#[derive(Debug, Default, serde::Serialize)]
+	pub struct CommodityClassification {
+		#[bt(
+			"BT-158",
+			"cbc:ItemClassificationCode",
+			1..1,
+			"Identifikator klasifikacije artikla",
+			"Item classification identifier"
+		)]
+		pub code: String,
+		#[bt("BT-158", "cbc:ItemClassificationCode/@listID", 1..1, "Identifikator sheme", "Scheme identifier")]
+		pub list_id: Option<String>,
+		#[bt(
+			"BT-158",
+			"cbc:ItemClassificationCode/@listVersionID",
+			0..1,
+			"Identifikator verzije sheme",
+			"Scheme version identifier"
+		)]
+		pub list_version_id: Option<String>,
+	}
+}
+
+mapped! {
+	#[derive(Debug, Default, serde::Serialize)]
+	pub struct ClassifiedTaxCategory {
+		#[bt("BT-151", "cbc:ID", 1..1, "Šifra kategorije PDV-a obračunate stavke", "Invoiced item VAT category code")]
+		pub id: String,
+		#[bt("BT-152", "cbc:Percent", 0..1, "Stopa PDV-a obračunate stavke", "Invoiced item VAT rate")]
+		pub percent: Option<String>,
+		#[bt("", "cac:TaxScheme/cbc:ID", 0..1, "Porezna shema", "Tax scheme")]
+		pub tax_scheme_id: Option<String>,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const INVOICE: &str = include_str!("../tests/data/invoice.xml");
+
+	// Valuta obračuna PDV-a USD s drugim TaxTotal u valuti currency
+	fn with_tax_currency(currency: &str) -> XmlData {
+		let xml = INVOICE
+			.replace(
+				"<cbc:DocumentCurrencyCode>EUR</cbc:DocumentCurrencyCode>",
+				"<cbc:DocumentCurrencyCode>EUR</cbc:DocumentCurrencyCode><cbc:TaxCurrencyCode>USD</cbc:TaxCurrencyCode>",
+			)
+			.replace(
+				"<cac:LegalMonetaryTotal>",
+				&std::format!(
+					r#"<cac:TaxTotal><cbc:TaxAmount currencyID="{}">270.00</cbc:TaxAmount></cac:TaxTotal><cac:LegalMonetaryTotal>"#,
+					currency
+				),
+			);
+		crate::from_str(&xml).unwrap()
+	}
+
+	#[test]
+	fn matching_currency() {
+		let xml_data = crate::from_str(INVOICE).unwrap();
+		assert!(xml_data.currency_mismatches().is_empty());
+		assert_eq!(xml_data.tax_total().unwrap().to_string(), "250.00 EUR");
+		assert_eq!(xml_data.tax_total_in_tax_currency(), None);
+		assert_eq!(xml_data.tax_totals[0].tax_amount_field("EUR").id, "BT-110");
+	}
+
+	#[test]
+	fn mismatched_currency() {
+		let xml = INVOICE.replace(r#"<cbc:PayableAmount currencyID="EUR">"#, r#"<cbc:PayableAmount currencyID="USD">"#);
+		let xml_data = crate::from_str(&xml).unwrap();
+		let [invalid] = xml_data.currency_mismatches().try_into().unwrap();
+		assert_eq!(invalid.field.id, "BT-115");
+		assert_eq!(invalid.path, "/Invoice/cac:LegalMonetaryTotal/cbc:PayableAmount");
+		assert_eq!(invalid.value, "USD");
+		assert!(matches!(invalid.error, CurrencyError::NotDocumentCurrency(ref x) if x == "EUR"));
+
+		// Ukupni iznos PDV-a u valuti računa ne postoji
+		let xml = INVOICE.replace(r#"<cbc:TaxAmount currencyID="EUR">"#, r#"<cbc:TaxAmount currencyID="GBP">"#);
+		let xml_data = crate::from_str(&xml).unwrap();
+		assert_eq!(xml_data.tax_total(), None);
+		let [invalid] = xml_data.currency_mismatches().try_into().unwrap();
+		assert_eq!(invalid.path, "/Invoice/cac:TaxTotal[1]/cbc:TaxAmount/@currencyID");
+		assert_eq!(invalid.value, "GBP");
+	}
+
+	#[test]
+	fn second_tax_total() {
+		let xml_data = with_tax_currency("USD");
+		assert!(xml_data.currency_mismatches().is_empty());
+		assert_eq!(xml_data.tax_total().unwrap().to_string(), "250.00 EUR");
+		assert_eq!(xml_data.tax_total_in_tax_currency().unwrap().to_string(), "270.00 USD");
+		let ids: Vec<&str> = xml_data
+			.tax_totals
+			.iter()
+			.map(|x| x.tax_amount_field("EUR").id)
+			.collect();
+		assert_eq!(ids, ["BT-110", "BT-111"]);
+
+		// Drugi TaxTotal nije ni u valuti računa ni u valuti obračuna PDV-a
+		let xml_data = with_tax_currency("GBP");
+		assert_eq!(xml_data.tax_total_in_tax_currency(), None);
+		let errors: Vec<(String, String)> = xml_data
+			.currency_mismatches()
+			.into_iter()
+			.map(|x| (x.path, x.error.to_string()))
+			.collect();
+		assert_eq!(
+			errors,
+			[
+				(
+					"/Invoice/cac:TaxTotal[2]/cbc:TaxAmount/@currencyID".to_owned(),
+					"iznos nije u valuti računa EUR".to_owned()
+				),
+				("/Invoice/cbc:TaxCurrencyCode".to_owned(), CurrencyError::MissingTaxTotal.to_string()),
+			]
+		);
+	}
+
+	#[test]
+	fn missing_tax_amount_in_tax_currency() {
+		let mut xml_data = with_tax_currency("USD");
+		xml_data.tax_totals[1].tax_amount = None;
+		let missing = xml_data.missing_fields();
+		let missing: Vec<(&str, &str)> = missing.iter().map(|x| (x.field.id, x.path.as_str())).collect();
+		assert_eq!(missing, [("BT-111", "/Invoice/cac:TaxTotal[2]/cbc:TaxAmount")]);
+	}
+
+	#[test]
+	fn invalid_kpd() {
+		let mut line = InvoiceLine::default();
+		assert!(line.invalid_kpd(1).is_none());
+		assert_eq!(line.item.kpd(), Err(KpdError::Missing));
+
+		for (code, error) in [
+			("62.01.11", None),
+			("62.1.11", Some(KpdError::Malformed("62.1.11".to_owned()))),
+			("04.01.01", Some(KpdError::UnknownDivision("04.01.01".to_owned()))),
+		] {
+			line.item.commodity_classifications = vec![
+				CommodityClassification {
+					code: "1234".to_owned(),
+					list_id: Some("STI".to_owned()),
+					..Default::default()
+				},
+				CommodityClassification {
+					code: code.to_owned(),
+					list_id: Some(kpd::KPD_LIST_ID.to_owned()),
+					..Default::default()
+				},
+			];
+			let invalid = line.invalid_kpd(3);
+			assert_eq!(invalid.as_ref().map(|x| &x.error), error.as_ref(), "{}", code);
+			if let Some(invalid) = invalid {
+				assert_eq!(
+					invalid.path,
+					"/Invoice/cac:InvoiceLine[3]/cac:Item/cac:CommodityClassification[2]/cbc:ItemClassificationCode"
+				);
+				assert_eq!(invalid.value, code);
+			}
+		}
+	}
+}
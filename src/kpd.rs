@@ -0,0 +1,152 @@
+// Klasifikacija proizvoda po djelatnostima (KPD)
+//
+// Šifra se provjerava točno, prema punoj klasifikaciji KPD 2025 koju objavljuje Državni zavod za statistiku.
+// Klasifikacija ima nekoliko tisuća šifri i mijenja se neovisno o programu, pa se učitava iz datoteke
+// (šifra<TAB>naziv, npr. izvoz iz DZS-ove tablice) s KpdTable::load. Ugrađeni kpd.tsv sadrži samo odjeljke
+// (prve dvije znamenke). Bez pune klasifikacije provjeravaju se samo oblik šifre i postojanje njezina odjeljka,
+// pa šifra koja prođe provjeru ne mora postojati u KPD 2025.
+
+use std::{collections::HashMap, path::Path, sync::LazyLock};
+
+// listID kojim se u cbc:ItemClassificationCode označava KPD šifra
+pub const KPD_LIST_ID: &str = "CG";
+
+static DIVISIONS: LazyLock<HashMap<&'static str, &'static str>> = LazyLock::new(|| {
+	include_str!("kpd.tsv")
+		.lines()
+		.filter_map(|line| line.split_once('\t'))
+		.collect()
+});
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KpdError {
+	// Stavka nema KPD šifru
+	Missing,
+	// Šifra nije u obliku NN.NN.NN
+	Malformed(String),
+	// Šifra je ispravnog oblika, ali je nema u klasifikaciji
+	Unknown(String),
+	// Odjeljak šifre (prve dvije znamenke) nije u KPD 2025
+	UnknownDivision(String),
+}
+
+impl std::fmt::Display for KpdError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Missing => write!(f, "nedostaje KPD šifra"),
+			Self::Malformed(code) => write!(f, "neispravan oblik KPD šifre: {}", code),
+			Self::Unknown(code) => write!(f, "nepoznata KPD šifra: {}", code),
+			Self::UnknownDivision(code) => write!(f, "odjeljak KPD šifre {} nije u KPD 2025", code),
+		}
+	}
+}
+
+pub fn is_well_formed(code: &str) -> bool {
+	let bytes = code.as_bytes();
+	bytes.len() == 8
+		&& bytes.iter().enumerate().all(|(i, b)| match i {
+			2 | 5 => *b == b'.',
+			_ => b.is_ascii_digit(),
+		})
+}
+
+// Šifra bez razmaka, ako je ispravnog oblika i njezin odjeljak postoji
+pub fn check(code: &str) -> Result<&str, KpdError> {
+	let code = code.trim();
+	if !is_well_formed(code) {
+		return Err(KpdError::Malformed(code.to_owned()));
+	}
+	match division(code) {
+		Some(_) => Ok(code),
+		None => Err(KpdError::UnknownDivision(code.to_owned())),
+	}
+}
+
+// Naziv odjeljka šifre iz ugrađene tablice, npr. 62 za 62.01.11
+pub fn division(code: &str) -> Option<&'static str> {
+	DIVISIONS.get(code.trim().get(..2)?).copied()
+}
+
+// Puna klasifikacija KPD 2025
+#[derive(Debug, Clone, Default)]
+pub struct KpdTable {
+	codes: HashMap<String, String>,
+}
+
+impl KpdTable {
+	pub fn load(path: &Path) -> std::io::Result<Self> {
+		Ok(Self::parse(&std::fs::read_to_string(path)?))
+	}
+
+	// Retci šifra<TAB>naziv; retci s višim razinama (odjeljak, skupina, razred) se preskaču
+	pub fn parse(text: &str) -> Self {
+		let codes = text
+			.lines()
+			.filter_map(|line| line.split_once('\t'))
+			.map(|(code, name)| (code.trim(), name.trim()))
+			.filter(|(code, _)| is_well_formed(code))
+			.map(|(code, name)| (code.to_owned(), name.to_owned()))
+			.collect();
+		Self { codes }
+	}
+
+	pub fn len(&self) -> usize {
+		self.codes.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.codes.is_empty()
+	}
+
+	// Naziv točno zadane šifre, bez traženja po nadređenim razinama
+	pub fn describe(&self, code: &str) -> Result<&str, KpdError> {
+		let code = check(code)?;
+		self.codes
+			.get(code)
+			.map(String::as_str)
+			.ok_or_else(|| KpdError::Unknown(code.to_owned()))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const TABLE: &str = "62\tUsluge računalnog programiranja\n\
+		62.01\tUsluge računalnog programiranja\n\
+		62.01.11\tUsluge izrade i razvoja IT aplikacija\n\
+		62.01.12\tUsluge izrade i razvoja IT mreža i sustava\n";
+
+	#[test]
+	fn exact_match() {
+		let table = KpdTable::parse(TABLE);
+		assert_eq!(table.len(), 2);
+		assert_eq!(table.describe(" 62.01.11 "), Ok("Usluge izrade i razvoja IT aplikacija"));
+		assert_eq!(table.describe("62.01.19"), Err(KpdError::Unknown("62.01.19".to_owned())));
+		assert_eq!(table.describe("62.01"), Err(KpdError::Malformed("62.01".to_owned())));
+	}
+
+	#[test]
+	fn well_formed() {
+		assert!(is_well_formed("01.11.11"));
+		for code in ["", "011111", "01.11.1", "01-11-11", "01.11.111", "0a.11.11"] {
+			assert!(!is_well_formed(code), "{}", code);
+		}
+	}
+
+	#[test]
+	fn checked_without_table() {
+		assert_eq!(check(" 62.01.11 "), Ok("62.01.11"));
+		assert_eq!(check("04.00.00"), Err(KpdError::UnknownDivision("04.00.00".to_owned())));
+		assert_eq!(check("00.01.01"), Err(KpdError::UnknownDivision("00.01.01".to_owned())));
+		assert_eq!(check("62.01"), Err(KpdError::Malformed("62.01".to_owned())));
+		assert_eq!(KpdTable::parse(TABLE).describe("04.01.01"), Err(KpdError::UnknownDivision("04.01.01".to_owned())));
+	}
+
+	#[test]
+	fn builtin_divisions() {
+		assert_eq!(division("62.01.11"), Some("Usluge računalnog programiranja, savjetovanja i povezane usluge"));
+		assert_eq!(division("04.00.00"), None);
+		assert_eq!(division("6"), None);
+	}
+}
@@ -0,0 +1,17 @@
+// Jezik prikaza, za nazive, brojeve i iznose
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, serde::Serialize)]
+pub enum Language {
+	#[default]
+	Hr,
+	En,
+}
+
+impl Language {
+	// Znak za decimale i za odvajanje tisućica, 1.234,56 ili 1,234.56
+	pub fn separators(self) -> (char, char) {
+		match self {
+			Self::Hr => (',', '.'),
+			Self::En => ('.', ','),
+		}
+	}
+}
@@ -0,0 +1,70 @@
+/*
+	Referentni Dokument: https://www.fina.hr/ngsite/content/download/12522/187213/1
+*/
+
+pub mod c14n;
+pub mod codelist;
+pub mod date;
+pub mod decimal;
+mod error;
+pub mod extensions;
+pub mod iban;
+mod invoice;
+pub mod kpd;
+pub mod language;
+mod limits;
+pub mod mapping;
+pub mod money;
+pub mod namespace;
+pub mod oib;
+mod parser;
+mod path;
+mod reader;
+pub mod reference;
+pub mod signature;
+pub mod specification;
+mod stream;
+pub mod units;
+pub mod words;
+pub mod xmldsig;
+
+pub use error::{ParseError, ParseErrorKind};
+pub use invoice::*;
+pub use limits::{Limit, Limits};
+use std::io::Read;
+pub use stream::InvoiceStream;
+
+#[derive(Debug, Default, Clone)]
+pub struct ParseOptions {
+	// Elemente i atribute koji nisu u tablici polja sprema u XmlData::unknown
+	pub lossless: bool,
+	// Ograničenja za dokumente iz nepouzdanih izvora, zadana su uključena
+	pub limits: Limits,
+}
+
+pub fn parse_invoice<R: Read>(reader: R) -> Result<XmlData, ParseError> {
+	parse_invoice_with(reader, &ParseOptions::default())
+}
+
+pub fn parse_invoice_with<R: Read>(reader: R, options: &ParseOptions) -> Result<XmlData, ParseError> {
+	let mut parser = reader::Reader::new(reader, &options.limits);
+	let mut xml_data = XmlData::default();
+	let mut path = path::Path::default();
+	parser::read_root(&mut parser, &mut path)?;
+	parser::main_logic(&mut parser, &mut xml_data, &mut path, &mut Vec::new(), options, false)?;
+
+	Ok(xml_data)
+}
+
+// Stavke računa čita jednu po jednu, bez držanja svih u memoriji, npr. za račune s više od 100 000 stavki
+pub fn parse_invoice_stream<R: Read>(reader: R, options: &ParseOptions) -> Result<InvoiceStream<R>, ParseError> {
+	InvoiceStream::new(reader, options)
+}
+
+pub fn from_str(xml: &str) -> Result<XmlData, ParseError> {
+	parse_invoice(xml.as_bytes())
+}
+
+pub fn from_bytes(xml: &[u8]) -> Result<XmlData, ParseError> {
+	parse_invoice(xml)
+}
@@ -0,0 +1,70 @@
+// Ograničenja za dokumente iz nepouzdanih izvora
+//
+// Zadane vrijednosti su dovoljne za stvarne račune s desecima tisuća stavki, a sprječavaju da jedan
+// zlonamjeran dokument potroši svu memoriju. DTD i entiteti se odbijaju uvijek, neovisno o ograničenjima.
+
+#[derive(Debug, Clone)]
+pub struct Limits {
+	// Najveća veličina dokumenta u bajtovima
+	pub max_document_size: u64,
+	// Najveća dubina ugniježđenih elemenata, uključujući korijenski
+	pub max_depth: usize,
+	// Najveći broj atributa na jednom elementu
+	pub max_attributes: usize,
+	// Najveća duljina teksta jednog elementa u bajtovima
+	pub max_text_length: usize,
+	// Najveći broj stavki računa (cac:InvoiceLine), ne primjenjuje se na InvoiceStream
+	pub max_invoice_lines: usize,
+	// Najveća duljina base64 sadržaja jednog privitka (cbc:EmbeddedDocumentBinaryObject) u bajtovima
+	pub max_attachment_size: usize,
+}
+
+impl Default for Limits {
+	fn default() -> Self {
+		Self {
+			max_document_size: 100 * 1024 * 1024,
+			max_depth: 64,
+			max_attributes: 64,
+			max_text_length: 1024 * 1024,
+			max_invoice_lines: 100_000,
+			max_attachment_size: 32 * 1024 * 1024,
+		}
+	}
+}
+
+impl Limits {
+	// Bez ograničenja, samo za dokumente iz pouzdanih izvora
+	pub fn unlimited() -> Self {
+		Self {
+			max_document_size: u64::MAX,
+			max_depth: usize::MAX,
+			max_attributes: usize::MAX,
+			max_text_length: usize::MAX,
+			max_invoice_lines: usize::MAX,
+			max_attachment_size: usize::MAX,
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Limit {
+	DocumentSize,
+	Depth,
+	Attributes,
+	TextLength,
+	InvoiceLines,
+	AttachmentSize,
+}
+
+impl std::fmt::Display for Limit {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str(match self {
+			Limit::DocumentSize => "veličina dokumenta",
+			Limit::Depth => "dubina elemenata",
+			Limit::Attributes => "broj atributa",
+			Limit::TextLength => "duljina teksta",
+			Limit::InvoiceLines => "broj stavki",
+			Limit::AttachmentSize => "veličina privitka",
+		})
+	}
+}
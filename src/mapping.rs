@@ -0,0 +1,750 @@
+// Tablica poslovnih pojmova (BT) i grupa (BG)
+//
+// Svako polje modela opisano je jednom, u makru `mapped!`: oznaka iz norme EN 16931, UBL putanja, kardinalnost
+// te hrvatski i engleski naziv. Iz tog opisa nastaju parsiranje, provjera obaveznih polja i nazivi za prikaz.
+
+use crate::{
+	codelist::{CodeLabel, CodeList},
+	date::Date,
+	decimal::{Amount, Quantity},
+};
+use std::fmt::{self, Display};
+use xml::attribute::OwnedAttribute;
+
+#[derive(Debug)]
+pub struct Field {
+	// Oznaka poslovnog pojma ili grupe, npr. BT-1 ili BG-22, prazna ako je norma ne definira
+	pub id: &'static str,
+	// Putanja relativna roditeljskoj grupi, vrijednost atributa navodi se kao cbc:ID/@schemeID
+	pub path: &'static str,
+	pub cardinality: Cardinality,
+	pub label_hr: &'static str,
+	pub label_en: &'static str,
+	pub whitespace: Whitespace,
+	// Ključ u JSON izlazu
+	pub key: &'static str,
+	// Polja grupe, prazno za poslovne pojmove
+	pub children: &'static [Field],
+	// Atributi elementa koje čita sam tip vrijednosti, npr. currencyID iznosa
+	pub attributes: &'static [&'static str],
+}
+
+impl Field {
+	// Putanja elementa i ime atributa, ako se vrijednost čita iz atributa
+	fn element(&self) -> (&'static str, Option<&'static str>) {
+		match self.path.split_once("/@") {
+			Some((element, attribute)) => (element, Some(attribute)),
+			None => (self.path, None),
+		}
+	}
+
+	pub fn is_required(&self) -> bool {
+		self.cardinality.min > 0
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cardinality {
+	pub min: u32,
+	// None označava neograničen broj ponavljanja (n)
+	pub max: Option<u32>,
+}
+
+impl Display for Cardinality {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self.max {
+			Some(max) => write!(f, "{}..{}", self.min, max),
+			None => write!(f, "{}..n", self.min),
+		}
+	}
+}
+
+// Obrada razmaka u tekstu elementa
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Whitespace {
+	// Svaki niz razmaka i prijeloma redaka postaje jedan razmak, npr. za nazive
+	Collapse,
+	// Prijelomi redaka se čuvaju, a razmaci unutar retka sažimaju, npr. za opise i napomene
+	PreserveLines,
+}
+
+impl Whitespace {
+	pub fn apply(self, text: &str) -> String {
+		match self {
+			Self::Collapse => collapse(text),
+			Self::PreserveLines => {
+				let lines: Vec<String> = text.lines().map(collapse).collect();
+				let first = lines.iter().position(|x| !x.is_empty()).unwrap_or(lines.len());
+				let last = lines.iter().rposition(|x| !x.is_empty()).map_or(first, |x| x + 1);
+				lines[first..last].join("\n")
+			},
+		}
+	}
+}
+
+fn collapse(text: &str) -> String {
+	text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// Obavezno polje koje nije pronađeno u dokumentu
+#[derive(Debug)]
+pub struct Missing {
+	pub field: &'static Field,
+	// Puna putanja, ponovljene grupe označene su rednim brojem, npr. /Invoice/cac:InvoiceLine[2]/cbc:ID
+	pub path: String,
+}
+
+impl Display for Missing {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if self.field.id.is_empty() {
+			write!(f, "nedostaje {} ({})", self.field.label_hr, self.path)
+		} else {
+			write!(f, "nedostaje {} {} ({})", self.field.id, self.field.label_hr, self.path)
+		}
+	}
+}
+
+// Polje čija vrijednost nije prošla provjeru, npr. OIB s pogrešnom kontrolnom znamenkom
+#[derive(Debug)]
+pub struct Invalid<E> {
+	pub field: &'static Field,
+	pub path: String,
+	pub value: String,
+	pub error: E,
+}
+
+impl<E> Invalid<E> {
+	// Polje s ključem key iz strukture T na putanji parent
+	pub(crate) fn new<T: Mapped>(parent: &str, key: &str, value: &str, error: E) -> Self {
+		let field = self::field::<T>(key);
+		Self {
+			field,
+			path: std::format!("{}/{}", parent, field.path),
+			value: value.to_owned(),
+			error,
+		}
+	}
+}
+
+impl<E: Display> Display for Invalid<E> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self.field.id.is_empty() {
+			true => write!(f, "neispravna vrijednost {}", self.field.label_hr)?,
+			false => write!(f, "neispravna vrijednost {} {}", self.field.id, self.field.label_hr)?,
+		}
+		write!(f, " ({}): \"{}\", {}", self.path, self.value, self.error)
+	}
+}
+
+// Struktura čija su polja opisana makrom `mapped!`
+pub trait Mapped: Default {
+	const FIELDS: &'static [Field];
+
+	// Početak elementa polja zadanog nizom indeksa u FIELDS, prvi je indeks polja ove strukture, a ostali
+	// indeksi polja unutar grupa, vidi Path::routes
+	// Vraća true ako element nosi tekstualnu vrijednost, a Err s imenom obaveznog atributa koji nedostaje
+	fn start(&mut self, route: &[usize], attributes: &[OwnedAttribute]) -> Result<bool, &'static str>;
+
+	// Err s poljem čija vrijednost nije ispravnog oblika
+	fn text(&mut self, route: &[usize], attributes: &[OwnedAttribute], text: &str) -> Result<(), &'static Field>;
+
+	fn missing(&self, path: &str, out: &mut Vec<Missing>);
+
+	// Vrijednosti polja koja su šifre iz popisa, vidi CodeList::for_field
+	fn codes(&self, path: &str, out: &mut Vec<CodeLabel>);
+}
+
+// Tip polja: tekstualna vrijednost (String, Option<String>, Vec<String>), vrijednost s tipom (Option<T: Value>)
+// ili grupa (T, Option<T>, Vec<T>)
+pub trait Entry {
+	const CHILDREN: &'static [Field] = &[];
+	const ATTRIBUTES: &'static [&'static str] = &[];
+
+	// Prazan route označava element samog polja
+	fn start(&mut self, field: &Field, route: &[usize], attributes: &[OwnedAttribute]) -> Result<bool, &'static str>;
+
+	fn text(
+		&mut self,
+		field: &'static Field,
+		route: &[usize],
+		attributes: &[OwnedAttribute],
+		text: &str,
+	) -> Result<(), &'static Field>;
+
+	fn missing(&self, field: &'static Field, parent: &str, out: &mut Vec<Missing>);
+
+	fn codes(&self, _: &'static Field, _: &str, _: &mut Vec<CodeLabel>) {}
+}
+
+fn start_value(field: &Field, attributes: &[OwnedAttribute], set: impl FnOnce(String)) -> Result<bool, &'static str> {
+	let Some(name) = field.element().1 else {
+		return Ok(true);
+	};
+	match attribute(attributes, name) {
+		Some(value) => set(value),
+		None if field.is_required() => return Err(name),
+		None => (),
+	}
+
+	Ok(false)
+}
+
+fn code_value(field: &'static Field, parent: &str, value: &str, out: &mut Vec<CodeLabel>) {
+	if let Some(list) = CodeList::for_field(field)
+		&& !value.trim().is_empty()
+	{
+		out.push(CodeLabel::new(list, field, std::format!("{}/{}", parent, field.path), value));
+	}
+}
+
+// Obavezni atribut prijavljuje se već pri parsiranju, i to samo ako postoji njegov element
+fn missing_value(field: &'static Field, parent: &str, is_empty: bool, out: &mut Vec<Missing>) {
+	if is_empty && field.is_required() && field.element().1.is_none() {
+		out.push(Missing { field, path: std::format!("{}/{}", parent, field.path) });
+	}
+}
+
+impl Entry for String {
+	fn start(&mut self, field: &Field, _: &[usize], attributes: &[OwnedAttribute]) -> Result<bool, &'static str> {
+		start_value(field, attributes, |value| *self = value)
+	}
+
+	fn text(
+		&mut self,
+		field: &'static Field,
+		_: &[usize],
+		_: &[OwnedAttribute],
+		text: &str,
+	) -> Result<(), &'static Field> {
+		// Polje koje se čita iz atributa dijeli element s drugim poljima, ali nema tekst
+		if field.element().1.is_none() {
+			*self = field.whitespace.apply(text);
+		}
+		Ok(())
+	}
+
+	fn missing(&self, field: &'static Field, parent: &str, out: &mut Vec<Missing>) {
+		missing_value(field, parent, self.is_empty(), out);
+	}
+
+	fn codes(&self, field: &'static Field, parent: &str, out: &mut Vec<CodeLabel>) {
+		code_value(field, parent, self, out);
+	}
+}
+
+impl Entry for Option<String> {
+	fn start(&mut self, field: &Field, _: &[usize], attributes: &[OwnedAttribute]) -> Result<bool, &'static str> {
+		start_value(field, attributes, |value| *self = Some(value))
+	}
+
+	fn text(
+		&mut self,
+		field: &'static Field,
+		_: &[usize],
+		_: &[OwnedAttribute],
+		text: &str,
+	) -> Result<(), &'static Field> {
+		// Polje koje se čita iz atributa dijeli element s drugim poljima, ali nema tekst
+		if field.element().1.is_none() {
+			*self = Some(field.whitespace.apply(text));
+		}
+		Ok(())
+	}
+
+	fn missing(&self, field: &'static Field, parent: &str, out: &mut Vec<Missing>) {
+		missing_value(field, parent, self.as_deref().is_none_or(str::is_empty), out);
+	}
+
+	fn codes(&self, field: &'static Field, parent: &str, out: &mut Vec<CodeLabel>) {
+		if let Some(value) = self {
+			code_value(field, parent, value, out);
+		}
+	}
+}
+
+impl Entry for Vec<String> {
+	fn start(&mut self, field: &Field, _: &[usize], attributes: &[OwnedAttribute]) -> Result<bool, &'static str> {
+		start_value(field, attributes, |value| self.push(value))
+	}
+
+	fn text(
+		&mut self,
+		field: &'static Field,
+		_: &[usize],
+		_: &[OwnedAttribute],
+		text: &str,
+	) -> Result<(), &'static Field> {
+		// Polje koje se čita iz atributa dijeli element s drugim poljima, ali nema tekst
+		// Prazan element, npr. <cbc:Note/>, ne dodaje vrijednost
+		let text = field.whitespace.apply(text);
+		if field.element().1.is_none() && !text.is_empty() {
+			self.push(text);
+		}
+		Ok(())
+	}
+
+	fn missing(&self, field: &'static Field, parent: &str, out: &mut Vec<Missing>) {
+		missing_value(field, parent, self.is_empty(), out);
+	}
+
+	fn codes(&self, field: &'static Field, parent: &str, out: &mut Vec<CodeLabel>) {
+		for value in self {
+			code_value(field, parent, value, out);
+		}
+	}
+}
+
+impl<T: Mapped> Entry for T {
+	const CHILDREN: &'static [Field] = T::FIELDS;
+
+	fn start(&mut self, _: &Field, route: &[usize], attributes: &[OwnedAttribute]) -> Result<bool, &'static str> {
+		match route {
+			[] => Ok(false),
+			_ => Mapped::start(self, route, attributes),
+		}
+	}
+
+	fn text(
+		&mut self,
+		_: &'static Field,
+		route: &[usize],
+		attributes: &[OwnedAttribute],
+		text: &str,
+	) -> Result<(), &'static Field> {
+		match route {
+			[] => Ok(()),
+			_ => Mapped::text(self, route, attributes, text),
+		}
+	}
+
+	fn missing(&self, field: &'static Field, parent: &str, out: &mut Vec<Missing>) {
+		Mapped::missing(self, &std::format!("{}/{}", parent, field.path), out);
+	}
+
+	fn codes(&self, field: &'static Field, parent: &str, out: &mut Vec<CodeLabel>) {
+		Mapped::codes(self, &std::format!("{}/{}", parent, field.path), out);
+	}
+}
+
+impl<T: Mapped> Entry for Option<T> {
+	const CHILDREN: &'static [Field] = T::FIELDS;
+
+	fn start(&mut self, _: &Field, route: &[usize], attributes: &[OwnedAttribute]) -> Result<bool, &'static str> {
+		let group = self.get_or_insert_with(T::default);
+		match route {
+			[] => Ok(false),
+			_ => Mapped::start(group, route, attributes),
+		}
+	}
+
+	fn text(
+		&mut self,
+		_: &'static Field,
+		route: &[usize],
+		attributes: &[OwnedAttribute],
+		text: &str,
+	) -> Result<(), &'static Field> {
+		match (route, self) {
+			([], _) | (_, None) => Ok(()),
+			(_, Some(group)) => Mapped::text(group, route, attributes, text),
+		}
+	}
+
+	fn missing(&self, field: &'static Field, parent: &str, out: &mut Vec<Missing>) {
+		match self {
+			Some(group) => Mapped::missing(group, &std::format!("{}/{}", parent, field.path), out),
+			None => missing_value(field, parent, true, out),
+		}
+	}
+
+	fn codes(&self, field: &'static Field, parent: &str, out: &mut Vec<CodeLabel>) {
+		if let Some(group) = self {
+			Mapped::codes(group, &std::format!("{}/{}", parent, field.path), out);
+		}
+	}
+}
+
+// Svaki početak elementa grupe dodaje novi član, podelementi se odnose na posljednjeg
+impl<T: Mapped> Entry for Vec<T> {
+	const CHILDREN: &'static [Field] = T::FIELDS;
+
+	fn start(&mut self, _: &Field, route: &[usize], attributes: &[OwnedAttribute]) -> Result<bool, &'static str> {
+		match (route, self.last_mut()) {
+			([], _) => {
+				self.push(T::default());
+				Ok(false)
+			},
+			(_, Some(group)) => Mapped::start(group, route, attributes),
+			(_, None) => Ok(false),
+		}
+	}
+
+	fn text(
+		&mut self,
+		_: &'static Field,
+		route: &[usize],
+		attributes: &[OwnedAttribute],
+		text: &str,
+	) -> Result<(), &'static Field> {
+		match (route, self.last_mut()) {
+			([], _) | (_, None) => Ok(()),
+			(_, Some(group)) => Mapped::text(group, route, attributes, text),
+		}
+	}
+
+	fn missing(&self, field: &'static Field, parent: &str, out: &mut Vec<Missing>) {
+		missing_value(field, parent, self.is_empty(), out);
+		for (i, group) in self.iter().enumerate() {
+			Mapped::missing(group, &std::format!("{}/{}[{}]", parent, field.path, i + 1), out);
+		}
+	}
+
+	fn codes(&self, field: &'static Field, parent: &str, out: &mut Vec<CodeLabel>) {
+		for (i, group) in self.iter().enumerate() {
+			Mapped::codes(group, &std::format!("{}/{}[{}]", parent, field.path, i + 1), out);
+		}
+	}
+}
+
+// Vrijednost s tipom, npr. iznos, količina ili datum, koja se čita iz teksta elementa i njegovih atributa
+pub trait Value: Sized {
+	// Atributi bez prostora imena koje parse čita, pa nisu nemapirani
+	const ATTRIBUTES: &'static [&'static str] = &[];
+
+	// Tekst je već obrađen prema Whitespace polja, None ako nije ispravnog oblika
+	fn parse(text: &str, attributes: &[OwnedAttribute]) -> Option<Self>;
+}
+
+// Option<T: Value> ne može biti opći impl uz Option<T: Mapped>, pa se navodi za svaki tip
+macro_rules! value_entry {
+	($($ty:ty),* $(,)?) => {$(
+		impl Entry for Option<$ty> {
+			const ATTRIBUTES: &'static [&'static str] = <$ty as Value>::ATTRIBUTES;
+
+			fn start(&mut self, _: &Field, _: &[usize], _: &[OwnedAttribute]) -> Result<bool, &'static str> {
+				Ok(true)
+			}
+
+			fn text(
+				&mut self,
+				field: &'static Field,
+				_: &[usize],
+				attributes: &[OwnedAttribute],
+				text: &str,
+			) -> Result<(), &'static Field> {
+				*self = Some(<$ty as Value>::parse(&field.whitespace.apply(text), attributes).ok_or(field)?);
+				Ok(())
+			}
+
+			fn missing(&self, field: &'static Field, parent: &str, out: &mut Vec<Missing>) {
+				missing_value(field, parent, self.is_none(), out);
+			}
+		}
+	)*};
+}
+
+value_entry!(Amount, Quantity, Date, bool);
+
+// xsd:boolean
+impl Value for bool {
+	fn parse(text: &str, _: &[OwnedAttribute]) -> Option<Self> {
+		match text {
+			"true" | "1" => Some(true),
+			"false" | "0" => Some(false),
+			_ => None,
+		}
+	}
+}
+
+// Vrijednost atributa bez prostora imena
+pub(crate) fn attribute(attributes: &[OwnedAttribute], name: &str) -> Option<String> {
+	attributes
+		.iter()
+		.find(|x| x.name.namespace.is_none() && x.name.local_name == name)
+		.map(|x| x.value.clone())
+}
+
+// Sva polja s punim putanjama, npr. za ispis tablice pojmova ili oznaka u prikazu
+pub fn fields<T: Mapped>(root: &str) -> Vec<(String, &'static Field)> {
+	fn walk(parent: &str, fields: &'static [Field], out: &mut Vec<(String, &'static Field)>) {
+		for field in fields {
+			let path = std::format!("{}/{}", parent, field.path);
+			walk(&path, field.children, out);
+			out.push((path, field));
+		}
+	}
+
+	let mut out = Vec::new();
+	walk(root, T::FIELDS, &mut out);
+	out
+}
+
+// Polje strukture T s ključem key, za polja koja se navode u kodu
+pub(crate) fn field<T: Mapped>(key: &str) -> &'static Field {
+	T::FIELDS
+		.iter()
+		.find(|x| x.key == key)
+		.expect("polje nije u tablici polja")
+}
+
+// Polje s danom oznakom (BT-x ili BG-x)
+pub fn find<T: Mapped>(id: &str) -> Option<&'static Field> {
+	fn walk(id: &str, fields: &'static [Field]) -> Option<&'static Field> {
+		fields.iter().find_map(|field| {
+			if field.id == id {
+				Some(field)
+			} else {
+				walk(id, field.children)
+			}
+		})
+	}
+
+	walk(id, T::FIELDS)
+}
+
+// Definira strukturu i njezinu tablicu polja
+//
+// Svako mapirano polje ima atribut
+// #[bt(<oznaka>, <putanja>, <kardinalnost>, <hrvatski naziv>, <engleski naziv>[, <obrada razmaka>])]
+// gdje je zadana obrada razmaka Whitespace::Collapse,
+// a polja koja se ne čitaju iz UBL putanja navode se u bloku `extra`.
+macro_rules! mapped {
+	(
+		$(#[$meta:meta])*
+		pub struct $name:ident {
+			$(
+				#[bt($id:literal, $path:literal, $min:literal .. $max:tt, $hr:literal, $en:literal $(, $whitespace:ident)?)]
+				pub $field:ident: $ty:ty,
+			)*
+		}
+		$(extra { $($extra:tt)* })?
+	) => {
+		$(#[$meta])*
+		pub struct $name {
+			$(pub $field: $ty,)*
+			$($($extra)*)?
+		}
+
+		impl $crate::mapping::Mapped for $name {
+			const FIELDS: &'static [$crate::mapping::Field] = &[$(
+				$crate::mapping::Field {
+					id: $id,
+					path: $path,
+					cardinality: $crate::mapping::Cardinality { min: $min, max: $crate::mapping::mapped!(@max $max) },
+					label_hr: $hr,
+					label_en: $en,
+					whitespace: $crate::mapping::mapped!(@whitespace $($whitespace)?),
+					key: stringify!($field),
+					children: <$ty as $crate::mapping::Entry>::CHILDREN,
+					attributes: <$ty as $crate::mapping::Entry>::ATTRIBUTES,
+				},
+			)*];
+
+			fn start(
+				&mut self,
+				route: &[usize],
+				attributes: &[xml::attribute::OwnedAttribute],
+			) -> Result<bool, &'static str> {
+				// Za svako polje po jedna funkcija, redom kao u FIELDS
+				type Start = fn(
+					&mut $name,
+					&$crate::mapping::Field,
+					&[usize],
+					&[xml::attribute::OwnedAttribute],
+				) -> Result<bool, &'static str>;
+				const START: &[Start] = &[$(
+					|x, field, route, attributes| $crate::mapping::Entry::start(&mut x.$field, field, route, attributes),
+				)*];
+
+				let (&i, rest) = route.split_first().expect("prazan niz indeksa polja");
+				START[i](self, &Self::FIELDS[i], rest, attributes)
+			}
+
+			fn text(
+				&mut self,
+				route: &[usize],
+				attributes: &[xml::attribute::OwnedAttribute],
+				text: &str,
+			) -> Result<(), &'static $crate::mapping::Field> {
+				type Text = fn(
+					&mut $name,
+					&'static $crate::mapping::Field,
+					&[usize],
+					&[xml::attribute::OwnedAttribute],
+					&str,
+				) -> Result<(), &'static $crate::mapping::Field>;
+				const TEXT: &[Text] = &[$(
+					|x, field, route, attributes, text| {
+						$crate::mapping::Entry::text(&mut x.$field, field, route, attributes, text)
+					},
+				)*];
+
+				let (&i, rest) = route.split_first().expect("prazan niz indeksa polja");
+				TEXT[i](self, &Self::FIELDS[i], rest, attributes, text)
+			}
+
+			fn missing(&self, path: &str, out: &mut Vec<$crate::mapping::Missing>) {
+				let mut fields = Self::FIELDS.iter();
+				$(
+					let field = fields.next().unwrap();
+					$crate::mapping::Entry::missing(&self.$field, field, path, out);
+				)*
+			}
+
+			fn codes(&self, path: &str, out: &mut Vec<$crate::codelist::CodeLabel>) {
+				let mut fields = Self::FIELDS.iter();
+				$(
+					let field = fields.next().unwrap();
+					$crate::mapping::Entry::codes(&self.$field, field, path, out);
+				)*
+			}
+		}
+	};
+	(@whitespace) => {
+		$crate::mapping::Whitespace::Collapse
+	};
+	(@whitespace $whitespace:ident) => {
+		$crate::mapping::Whitespace::$whitespace
+	};
+	(@max n) => {
+		None
+	};
+	(@max $max:literal) => {
+		Some($max)
+	};
+}
+
+pub(crate) use mapped;
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{ParseErrorKind, XmlData};
+
+	mapped! {
+		#[derive(Debug, Default)]
+		pub struct Group {
+			#[bt("BT-900", "cbc:ID", 1..1, "Oznaka", "Identifier")]
+			pub id: String,
+			#[bt("BT-901", "cbc:ID/@schemeID", 0..1, "Shema", "Scheme")]
+			pub scheme: Option<String>,
+			#[bt("BT-902", "cbc:Note", 0..n, "Napomena", "Note", PreserveLines)]
+			pub notes: Vec<String>,
+		}
+	}
+
+	const INVOICE: &str = include_str!("../tests/data/invoice.xml");
+
+	fn attributes(list: &[(&str, &str)]) -> Vec<OwnedAttribute> {
+		list.iter()
+			.map(|(name, value)| OwnedAttribute::new(name.parse().unwrap(), *value))
+			.collect()
+	}
+
+	#[test]
+	fn table() {
+		let [id, scheme, notes] = Group::FIELDS else {
+			panic!()
+		};
+		assert_eq!((id.id, id.path, id.key, id.is_required()), ("BT-900", "cbc:ID", "id", true));
+		assert_eq!(scheme.element(), ("cbc:ID", Some("schemeID")));
+		assert_eq!(notes.cardinality.to_string(), "0..n");
+		assert_eq!(notes.whitespace, Whitespace::PreserveLines);
+		assert_eq!(id.whitespace, Whitespace::Collapse);
+		assert_eq!(field::<Group>("notes").id, notes.id);
+		assert_eq!(find::<Group>("BT-901").map(|x| x.key), Some(scheme.key));
+		assert!(find::<Group>("BT-1").is_none());
+
+		let paths: Vec<String> = fields::<Group>("/G").into_iter().map(|(path, _)| path).collect();
+		assert_eq!(paths, ["/G/cbc:ID", "/G/cbc:ID/@schemeID", "/G/cbc:Note"]);
+	}
+
+	#[test]
+	fn start_and_text() {
+		let mut group = Group::default();
+		let scheme = attributes(&[("schemeID", "0088")]);
+		assert_eq!(Mapped::start(&mut group, &[0], &scheme), Ok(true));
+		assert_eq!(Mapped::start(&mut group, &[1], &scheme), Ok(false));
+		Mapped::text(&mut group, &[0], &scheme, " 1 \n 2 ").unwrap();
+		Mapped::text(&mut group, &[1], &scheme, "ignored").unwrap();
+		Mapped::text(&mut group, &[2], &[], "a  b\n\nc").unwrap();
+		assert_eq!(group.id, "1 2");
+		assert_eq!(group.scheme.as_deref(), Some("0088"));
+		assert_eq!(group.notes, ["a b\n\nc"]);
+
+		let mut out = Vec::new();
+		Mapped::missing(&Group::default(), "/G", &mut out);
+		let paths: Vec<&str> = out.iter().map(|x| x.path.as_str()).collect();
+		assert_eq!(paths, ["/G/cbc:ID"]);
+	}
+
+	// Atribut s prostorom imena nije atribut iz tablice polja
+	#[test]
+	fn attribute_without_namespace() {
+		let mut group = Group::default();
+		let namespaced = vec![OwnedAttribute::new(
+			xml::name::OwnedName::qualified("schemeID", "urn:example", Some("x")),
+			"0088",
+		)];
+		Mapped::start(&mut group, &[1], &namespaced).unwrap();
+		assert_eq!(group.scheme, None);
+
+		let xml = INVOICE.replace(
+			r#"<cbc:EndpointID schemeID="9934">94577403194"#,
+			r#"<cbc:EndpointID xmlns:x="urn:example" x:schemeID="9934">94577403194"#,
+		);
+		let e = crate::from_str(&xml).unwrap_err();
+		assert!(matches!(e.kind, ParseErrorKind::MissingAttribute("schemeID")), "{:?}", e.kind);
+		assert_eq!(e.path, "/Invoice/cac:AccountingSupplierParty/cac:Party/cbc:EndpointID");
+	}
+
+	#[test]
+	fn missing_fields() {
+		assert!(crate::from_str(INVOICE).unwrap().missing_fields().is_empty());
+
+		let xml = INVOICE
+			.replace("<cbc:ID>1-POSL1-1</cbc:ID>", "")
+			.replace("<cbc:ID>1</cbc:ID>", "");
+		let xml_data = crate::from_str(&xml).unwrap();
+		let missing = xml_data.missing_fields();
+		let missing: Vec<(&str, &str)> = missing.iter().map(|x| (x.field.id, x.path.as_str())).collect();
+		assert_eq!(
+			missing,
+			[
+				("BT-1", "/Invoice/cbc:ID"),
+				("BT-126", "/Invoice/cac:InvoiceLine[1]/cbc:ID")
+			]
+		);
+
+		let missing = XmlData::default().missing_fields();
+		assert!(
+			missing
+				.iter()
+				.any(|x| x.field.id == "BG-25" && x.path == "/Invoice/cac:InvoiceLine")
+		);
+	}
+
+	#[test]
+	fn invalid_value() {
+		let xml = INVOICE.replace("<cbc:IssueDate>2025-01-15<", "<cbc:IssueDate>2025-13-45<");
+		let e = crate::from_str(&xml).unwrap_err();
+		assert_eq!(e.path, "/Invoice/cbc:IssueDate");
+		let ParseErrorKind::InvalidValue { field, value } = e.kind else {
+			panic!("{:?}", e.kind)
+		};
+		assert_eq!((field.id, value.as_str()), ("BT-2", "2025-13-45"));
+
+		let xml = INVOICE.replace(r#"unitCode="H87">10<"#, r#"unitCode="H87">deset<"#);
+		let e = crate::from_str(&xml).unwrap_err();
+		assert_eq!(e.path, "/Invoice/cac:InvoiceLine/cbc:InvoicedQuantity");
+		assert!(matches!(e.kind, ParseErrorKind::InvalidValue { field, .. } if field.id == "BT-129"));
+	}
+
+	#[test]
+	fn missing_attribute() {
+		let xml = INVOICE.replace(r#"<cbc:EndpointID schemeID="9934">12345678903"#, "<cbc:EndpointID>12345678903");
+		let e = crate::from_str(&xml).unwrap_err();
+		assert_eq!(e.path, "/Invoice/cac:AccountingCustomerParty/cac:Party/cbc:EndpointID");
+		assert!(matches!(e.kind, ParseErrorKind::MissingAttribute("schemeID")));
+	}
+}
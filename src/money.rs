@@ -0,0 +1,150 @@
+// Ispis iznosa prema valuti
+//
+// Iznos se zaokružuje na broj decimala valute iz ISO 4217 (EUR 2, JPY 0, KWD 3), pa se 100, 100.0 i 100.000
+// ispisuju jednako. Znakovi za decimale i tisućice te položaj oznake valute ovise o jeziku:
+// 1.234,56 € ili 1.234,56 EUR na hrvatskom, €1,234.56 ili EUR 1,234.56 na engleskom.
+
+use crate::{
+	codelist::Currency,
+	decimal::{Amount, Decimal},
+	language::Language,
+};
+
+const SYMBOLS: &[(&str, &str)] = &[("EUR", "€"), ("GBP", "£"), ("HRK", "kn"), ("JPY", "¥"), ("USD", "$")];
+
+// Broj decimala valute iz iso4217.tsv, 2 za valute koje nisu u popisu
+pub fn minor_units(currency: &str) -> u32 {
+	Currency::from_code(currency).map_or(2, Currency::minor_units)
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CurrencyDisplay {
+	// Šifra valute, npr. EUR
+	#[default]
+	Code,
+	// Znak valute, npr. €, ili šifra ako valuta nema znak
+	Symbol,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AmountFormat {
+	pub language: Language,
+	pub currency: CurrencyDisplay,
+}
+
+impl AmountFormat {
+	pub fn new(language: Language, currency: CurrencyDisplay) -> Self {
+		Self { language, currency }
+	}
+
+	// Iznos zaokružen na decimale svoje valute, a za iznos bez valute na decimale valute računa
+	pub fn format(&self, amount: &Amount, document_currency: &str) -> String {
+		let currency = amount.currency.as_deref().unwrap_or(document_currency);
+		self.format_value(amount.value.round(minor_units(currency)), currency)
+	}
+
+	// Cijena se ne zaokružuje, jer jedinična cijena smije imati više decimala od valute (BT-146)
+	pub fn format_price(&self, amount: &Amount, document_currency: &str) -> String {
+		let currency = amount.currency.as_deref().unwrap_or(document_currency);
+		let (_, frac) = amount.value.digits();
+		let scale = (frac.trim_end_matches('0').len() as u32).max(minor_units(currency));
+		self.format_value(amount.value.round(scale), currency)
+	}
+
+	// Vrijednost s oznakom valute, bez zaokruživanja
+	pub fn format_value(&self, value: Decimal, currency: &str) -> String {
+		let number = self.number(value);
+		let currency = currency.trim();
+		let symbol = match self.currency {
+			CurrencyDisplay::Symbol => SYMBOLS.iter().find(|(code, _)| *code == currency).map(|(_, x)| *x),
+			CurrencyDisplay::Code => None,
+		};
+
+		match (self.language, symbol) {
+			(_, None) if currency.is_empty() => number,
+			(Language::Hr, Some(symbol)) => std::format!("{} {}", number, symbol),
+			(Language::Hr, None) => std::format!("{} {}", number, currency),
+			(Language::En, Some(symbol)) => match number.strip_prefix('-') {
+				Some(number) => std::format!("-{}{}", symbol, number),
+				None => std::format!("{}{}", symbol, number),
+			},
+			(Language::En, None) => std::format!("{} {}", currency, number),
+		}
+	}
+
+	// Broj sa znakovima za decimale i tisućice, npr. 1.234,56
+	pub fn number(&self, value: Decimal) -> String {
+		let (decimal, thousands) = self.language.separators();
+		let (int, frac) = value.digits();
+
+		let mut out = String::new();
+		if value.is_negative() && !value.is_zero() {
+			out.push('-');
+		}
+		for (i, digit) in int.chars().enumerate() {
+			if i > 0 && (int.len() - i) % 3 == 0 {
+				out.push(thousands);
+			}
+			out.push(digit);
+		}
+		if !frac.is_empty() {
+			out.push(decimal);
+			out.push_str(&frac);
+		}
+		out
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn amount(value: &str, currency: Option<&str>) -> Amount {
+		Amount::new(value.parse().unwrap(), currency.map(str::to_owned))
+	}
+
+	#[test]
+	fn minor_units_from_code_list() {
+		assert_eq!(minor_units("EUR"), 2);
+		assert_eq!(minor_units(" JPY "), 0);
+		assert_eq!(minor_units("KWD"), 3);
+		assert_eq!(minor_units("CLF"), 4);
+		// Valuta koje nema u popisu
+		assert_eq!(minor_units("XYZ"), 2);
+		assert_eq!(minor_units(""), 2);
+	}
+
+	#[test]
+	fn languages_and_currency_display() {
+		let value = amount("-1234.5", None);
+		let cases = [
+			(Language::Hr, CurrencyDisplay::Symbol, "-1.234,50 €"),
+			(Language::Hr, CurrencyDisplay::Code, "-1.234,50 EUR"),
+			(Language::En, CurrencyDisplay::Symbol, "-€1,234.50"),
+			(Language::En, CurrencyDisplay::Code, "EUR -1,234.50"),
+		];
+		for (language, currency, expected) in cases {
+			assert_eq!(AmountFormat::new(language, currency).format(&value, "EUR"), expected);
+		}
+	}
+
+	#[test]
+	fn rounding_to_currency() {
+		let format = AmountFormat::new(Language::Hr, CurrencyDisplay::Code);
+		assert_eq!(format.format(&amount("100", None), "EUR"), "100,00 EUR");
+		assert_eq!(format.format(&amount("1234.4", Some("JPY")), "EUR"), "1.234 JPY");
+		assert_eq!(format.format(&amount("1.5", Some("KWD")), "EUR"), "1,500 KWD");
+		assert_eq!(format.format(&amount("1.5", None), "XYZ"), "1,50 XYZ");
+		// Iznos koji se zaokružuje na nulu nema predznak
+		assert_eq!(format.format(&amount("-0.001", None), "EUR"), "0,00 EUR");
+		assert_eq!(format.format(&amount("1234567", None), ""), "1.234.567,00");
+	}
+
+	#[test]
+	fn prices_keep_decimals() {
+		let format = AmountFormat::new(Language::En, CurrencyDisplay::Symbol);
+		assert_eq!(format.format_price(&amount("0.1250", None), "EUR"), "€0.125");
+		assert_eq!(format.format_price(&amount("5", None), "EUR"), "€5.00");
+		assert_eq!(format.format_price(&amount("5", Some("JPY")), "EUR"), "¥5");
+	}
+}
@@ -0,0 +1,121 @@
+// Prostori imena UBL 2.1 i proširenja
+//
+// Elemente prepoznajemo po paru (prostor imena, lokalno ime), a ne po prefiksu koji je pošiljatelj odabrao.
+// Putanje se grade s kanonskim prefiksima iz tablice ispod, pa <Invoice xmlns="…Invoice-2"> i <ns2:Invoice> daju istu putanju.
+
+use crate::xmldsig::{DS, XADES};
+use xml::name::OwnedName;
+
+pub const INVOICE: &str = "urn:oasis:names:specification:ubl:schema:xsd:Invoice-2";
+pub const CAC: &str = "urn:oasis:names:specification:ubl:schema:xsd:CommonAggregateComponents-2";
+pub const CBC: &str = "urn:oasis:names:specification:ubl:schema:xsd:CommonBasicComponents-2";
+pub const EXT: &str = "urn:oasis:names:specification:ubl:schema:xsd:CommonExtensionComponents-2";
+pub const SIG: &str = "urn:oasis:names:specification:ubl:schema:xsd:CommonSignatureComponents-2";
+pub const SAC: &str = "urn:oasis:names:specification:ubl:schema:xsd:SignatureAggregateComponents-2";
+pub const SBC: &str = "urn:oasis:names:specification:ubl:schema:xsd:SignatureBasicComponents-2";
+pub const HREXTAC: &str = "urn:mfin.gov.hr:schema:xsd:HRExtensionAggregateComponents-1";
+
+// (prostor imena, kanonski prefiks), prazan prefiks znači bez prefiksa
+const PREFIXES: [(&str, &str); 10] = [
+	(INVOICE, ""),
+	(CAC, "cac"),
+	(CBC, "cbc"),
+	(EXT, "ext"),
+	(SIG, "sig"),
+	(SAC, "sac"),
+	(SBC, "sbc"),
+	(HREXTAC, "hrextac"),
+	(DS, "ds"),
+	(XADES, "xades"),
+];
+
+pub fn canonical_prefix(namespace: &str) -> Option<&'static str> {
+	PREFIXES
+		.iter()
+		.find(|(uri, _)| *uri == namespace)
+		.map(|(_, prefix)| *prefix)
+}
+
+// Dio putanje za element, npr. cbc:ID
+// Elementi iz nepoznatih prostora imena zapisuju se kao {prostor imena}lokalno ime
+pub fn path_segment(name: &OwnedName) -> String {
+	let mut segment = String::new();
+	push_segment(&mut segment, name);
+	segment
+}
+
+pub fn push_segment(buffer: &mut String, name: &OwnedName) {
+	match name.namespace.as_deref() {
+		None | Some("") => (),
+		Some(namespace) => match canonical_prefix(namespace) {
+			Some("") => (),
+			Some(prefix) => {
+				buffer.push_str(prefix);
+				buffer.push(':');
+			},
+			None => {
+				buffer.push('{');
+				buffer.push_str(namespace);
+				buffer.push('}');
+			},
+		},
+	}
+	buffer.push_str(&name.local_name);
+}
+
+pub fn is(name: &OwnedName, namespace: &str, local_name: &str) -> bool {
+	name.local_name == local_name && name.namespace.as_deref() == Some(namespace)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn prefixes() {
+		for (uri, prefix) in PREFIXES {
+			assert_eq!(canonical_prefix(uri), Some(prefix));
+		}
+		assert_eq!(canonical_prefix("urn:example"), None);
+		assert_eq!(canonical_prefix(""), None);
+
+		assert_eq!(path_segment(&OwnedName::qualified("ID", CBC, Some("ns2"))), "cbc:ID");
+		assert_eq!(path_segment(&OwnedName::qualified("Invoice", INVOICE, Some("inv"))), "Invoice");
+		assert_eq!(path_segment(&OwnedName::qualified("ID", "urn:example", Some("cbc"))), "{urn:example}ID");
+		assert_eq!(path_segment(&OwnedName::local("ID")), "ID");
+
+		assert!(is(&OwnedName::qualified("ID", CBC, Some("x")), CBC, "ID"));
+		assert!(!is(&OwnedName::qualified("ID", CAC, Some("cbc")), CBC, "ID"));
+	}
+
+	fn json(xml: &str) -> serde_json::Value {
+		let mut xml_data = crate::from_str(xml).unwrap();
+		// Izvorni XML proširenja nosi deklaracije prostora imena kako su napisane
+		xml_data.extensions.clear();
+		serde_json::to_value(&xml_data).unwrap()
+	}
+
+	// Isti račun s drugim prefiksima daje iste podatke
+	#[test]
+	fn rewritten_prefixes() {
+		let xml = include_str!("../tests/data/invoice.xml");
+		let expected = json(xml);
+		assert_eq!(expected["id"], "1-POSL1-1");
+
+		let renamed = xml
+			.replace("xmlns:cbc=", "xmlns:ns2=")
+			.replace("xmlns:cac=", "xmlns:ns3=")
+			.replace("cbc:", "ns2:")
+			.replace("cac:", "ns3:");
+		assert!(!renamed.contains("cbc:"));
+		assert_eq!(json(&renamed), expected);
+
+		let default = xml
+			.replace("<Invoice xmlns=", "<ns1:Invoice xmlns:ns1=")
+			.replace("</Invoice>", "</ns1:Invoice>")
+			.replace("xmlns:cbc=", "xmlns=")
+			.replace("cbc:", "");
+		assert!(!default.contains("<cbc:"));
+		assert_eq!(json(&default), expected);
+	}
+}
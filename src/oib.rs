@@ -0,0 +1,134 @@
+// OIB, osobni identifikacijski broj
+//
+// Jedanaest znamenki od kojih je zadnja kontrolna, izračunata po normi ISO 7064 MOD 11,10. Porezni broj za PDV
+// je OIB s prefiksom HR, koji se pri čitanju uklanja.
+
+use crate::mapping::{Invalid, Mapped};
+use std::{
+	fmt::{self, Display},
+	str::FromStr,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Oib(String);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OibError {
+	// Broj znamenki nakon uklanjanja prefiksa HR
+	Length(usize),
+	NotDigits,
+	Checksum { expected: u8, found: u8 },
+}
+
+impl Display for OibError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Length(length) => write!(f, "OIB ima 11 znamenki, a ne {}", length),
+			Self::NotDigits => write!(f, "OIB smije sadržavati samo znamenke"),
+			Self::Checksum { expected, found } => {
+				write!(f, "kontrolna znamenka je {}, a treba biti {}", found, expected)
+			},
+		}
+	}
+}
+
+impl std::error::Error for OibError {}
+
+impl Oib {
+	// Jedanaest znamenki, bez prefiksa HR
+	pub fn as_str(&self) -> &str {
+		&self.0
+	}
+
+	// Porezni broj za PDV, npr. HR94577403194
+	pub fn vat_number(&self) -> String {
+		std::format!("HR{}", self.0)
+	}
+}
+
+// ISO 7064 MOD 11,10, za OIB nad prvih deset znamenki, koristi se i za poziv na broj
+pub(crate) fn check_digit(digits: &[u8]) -> u8 {
+	let remainder = digits.iter().fold(10, |a, digit| {
+		let sum = match (a + digit - b'0') % 10 {
+			0 => 10,
+			x => x,
+		};
+		sum * 2 % 11
+	});
+	(11 - remainder) % 10
+}
+
+// Prihvaća OIB sa ili bez prefiksa HR
+impl FromStr for Oib {
+	type Err = OibError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let text = s.trim();
+		let text = text.strip_prefix("HR").unwrap_or(text);
+		if !text.bytes().all(|x| x.is_ascii_digit()) {
+			return Err(OibError::NotDigits);
+		}
+		if text.len() != 11 {
+			return Err(OibError::Length(text.len()));
+		}
+
+		let digits = text.as_bytes();
+		let expected = check_digit(&digits[..10]);
+		let found = digits[10] - b'0';
+		if expected != found {
+			return Err(OibError::Checksum { expected, found });
+		}
+
+		Ok(Self(text.to_owned()))
+	}
+}
+
+impl Display for Oib {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(&self.0)
+	}
+}
+
+impl serde::Serialize for Oib {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(&self.0)
+	}
+}
+
+// Polje stranke u kojem OIB nije ispravan
+pub type InvalidOib = Invalid<OibError>;
+
+// Provjerava vrijednost polja s ključem key iz strukture T na putanji parent, prazne vrijednosti se preskaču
+pub(crate) fn check<T: Mapped>(parent: &str, key: &str, value: Option<&str>, out: &mut Vec<InvalidOib>) {
+	let Some(value) = value.filter(|x| !x.trim().is_empty()) else {
+		return;
+	};
+	if let Err(error) = value.parse::<Oib>() {
+		out.push(Invalid::new::<T>(parent, key, value, error));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn valid() {
+		for text in ["94577403194", "69435151530", "HR94577403194", " 12345678903 "] {
+			assert!(text.parse::<Oib>().is_ok(), "{}", text);
+		}
+		let oib: Oib = "HR94577403194".parse().unwrap();
+		assert_eq!(oib.as_str(), "94577403194");
+		assert_eq!(oib.vat_number(), "HR94577403194");
+	}
+
+	#[test]
+	fn invalid() {
+		assert_eq!("94577403195".parse::<Oib>(), Err(OibError::Checksum { expected: 4, found: 5 }));
+		assert_eq!("9457740319".parse::<Oib>(), Err(OibError::Length(10)));
+		assert_eq!("HR945774031944".parse::<Oib>(), Err(OibError::Length(12)));
+		assert_eq!("9457740319A".parse::<Oib>(), Err(OibError::NotDigits));
+		assert_eq!("945 7740 3194".parse::<Oib>(), Err(OibError::NotDigits));
+		assert_eq!("".parse::<Oib>(), Err(OibError::Length(0)));
+	}
+}
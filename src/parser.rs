@@ -0,0 +1,479 @@
+use crate::{
+	ParseError, ParseErrorKind, ParseOptions,
+	extensions::RawExtension,
+	invoice::{UnknownElement, XmlData},
+	mapping::{Mapped, Whitespace},
+	namespace::{self, EXT, INVOICE},
+	path::{Element, Path},
+	reader::Reader,
+};
+use std::io::Read;
+use xml::{EmitterConfig, attribute::OwnedAttribute, reader::XmlEvent};
+
+pub fn next_event<R: Read>(parser: &mut Reader<R>, path: &Path) -> Result<XmlEvent, ParseError> {
+	parser.next(path)
+}
+
+// Korijenski element mora biti Invoice
+pub fn read_root<R: Read>(parser: &mut Reader<R>, path: &mut Path) -> Result<(), ParseError> {
+	loop {
+		match next_event(parser, path)? {
+			XmlEvent::StartElement { name, .. } => {
+				if !namespace::is(&name, INVOICE, "Invoice") {
+					return Err(ParseError::new(
+						parser,
+						path,
+						ParseErrorKind::UnsupportedRoot(namespace::path_segment(&name)),
+					));
+				}
+
+				path.push(&name);
+				return Ok(());
+			},
+			e @ XmlEvent::EndDocument => return Err(ParseError::unexpected(parser, path, &e)),
+			_ => (),
+		}
+	}
+}
+
+// Razlog zbog kojeg je main_logic vratio kontrolu
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Progress {
+	EndDocument,
+	// Pročitan je kraj stavke računa, samo ako se stavke čitaju jedna po jedna
+	InvoiceLine,
+}
+
+// unknown je indeks u xml_data.unknown za svaki otvoreni element, ako je zapisan
+// Uz streaming se nakon svake stavke računa vraća Progress::InvoiceLine, a stavka je posljednja u xml_data.invoice_lines
+pub fn main_logic<R: Read>(
+	parser: &mut Reader<R>,
+	xml_data: &mut XmlData,
+	path: &mut Path,
+	unknown: &mut Vec<Option<usize>>,
+	options: &ParseOptions,
+	streaming: bool,
+) -> Result<Progress, ParseError> {
+	loop {
+		let e = next_event(parser, path)?;
+		let (name, attributes) = match e {
+			XmlEvent::StartElement { name, attributes, .. } => (name, attributes),
+			XmlEvent::EndElement { .. } => {
+				if let Some(Some(i)) = unknown.pop() {
+					let element = &mut xml_data.unknown[i];
+					element.text = Whitespace::Collapse.apply(&element.text);
+				}
+				let line_end = streaming && path.relative() == "cac:InvoiceLine";
+				path.pop();
+				if line_end {
+					return Ok(Progress::InvoiceLine);
+				}
+				continue;
+			},
+			XmlEvent::Characters(chars) | XmlEvent::CData(chars) => {
+				if let Some(Some(i)) = unknown.last() {
+					xml_data.unknown[*i].text.push_str(&chars);
+				}
+				continue;
+			},
+			XmlEvent::EndDocument => return Ok(Progress::EndDocument),
+			_ => continue,
+		};
+
+		let element = path.push(&name);
+		if path.relative() == "ext:UBLExtensions" {
+			parse_ubl_extensions(parser, xml_data, path, options.lossless)?;
+			continue;
+		}
+
+		if options.lossless {
+			let attributes: Vec<(String, String)> = match element {
+				Element::Unknown => attributes.iter().collect::<Vec<_>>(),
+				_ => path.unmapped_attributes(&attributes).collect(),
+			}
+			.into_iter()
+			.map(|x| (namespace::path_segment(&x.name), x.value.clone()))
+			.collect();
+			if element == Element::Unknown || !attributes.is_empty() {
+				xml_data.unknown.push(UnknownElement {
+					path: path.as_str().to_owned(),
+					attributes,
+					text: String::new(),
+				});
+			}
+		}
+		// Tekst se bilježi samo za nepoznate elemente
+		let index = match element {
+			Element::Unknown if options.lossless => Some(xml_data.unknown.len() - 1),
+			_ => None,
+		};
+
+		if element != Element::Mapped {
+			unknown.push(index);
+			continue;
+		}
+
+		if !read_mapped(parser, xml_data, path, &attributes, options.lossless)? {
+			unknown.push(None);
+		}
+	}
+}
+
+// Upisuje atribute i tekst elementa iz tablice polja, poziva se nakon početka elementa
+// Vraća true ako je element nosio tekst, pa je pročitan do kraja i zatvoren
+fn read_mapped<R: Read>(
+	parser: &mut Reader<R>,
+	xml_data: &mut XmlData,
+	path: &mut Path,
+	attributes: &[OwnedAttribute],
+	lossless: bool,
+) -> Result<bool, ParseError> {
+	// Isti element može biti početak grupe i vrijednost ili nositi više atributa iz tablice polja
+	let mut has_text = false;
+	for route in path.routes() {
+		has_text |= Mapped::start(xml_data, route, attributes)
+			.map_err(|name| ParseError::new(parser, path, ParseErrorKind::MissingAttribute(name)))?;
+	}
+	if !has_text {
+		return Ok(false);
+	}
+
+	let text = read_text(parser, path, lossless.then_some(&mut xml_data.unknown))?;
+	for route in path.routes() {
+		Mapped::text(xml_data, route, attributes, &text).map_err(|field| {
+			let value = field.whitespace.apply(&text);
+			ParseError::new(parser, path, ParseErrorKind::InvalidValue { field, value })
+		})?;
+	}
+	path.pop();
+
+	Ok(true)
+}
+
+// Tekst do kraja trenutnog elementa, uključujući CDATA i razmake
+// Podelementi nisu dio vrijednosti, preskaču se, a uz unknown zapisuju kao nepoznati elementi
+// Kraj elementa se pročita, ali putanju zatvara pozivatelj
+fn read_text<R: Read>(
+	parser: &mut Reader<R>,
+	path: &mut Path,
+	mut unknown: Option<&mut Vec<UnknownElement>>,
+) -> Result<String, ParseError> {
+	let mut text = String::new();
+	// Indeks u unknown za svaki otvoreni podelement
+	let mut children: Vec<Option<usize>> = Vec::new();
+	loop {
+		match next_event(parser, path)? {
+			XmlEvent::Characters(chars) | XmlEvent::CData(chars) | XmlEvent::Whitespace(chars) => {
+				match (children.last(), unknown.as_deref_mut()) {
+					(None, _) => text.push_str(&chars),
+					(Some(Some(i)), Some(unknown)) => unknown[*i].text.push_str(&chars),
+					_ => (),
+				}
+			},
+			XmlEvent::StartElement { name, attributes, .. } => {
+				path.push(&name);
+				let index = unknown.as_deref_mut().map(|unknown| {
+					let attributes = attributes
+						.iter()
+						.map(|x| (namespace::path_segment(&x.name), x.value.clone()))
+						.collect();
+					unknown.push(UnknownElement {
+						path: path.as_str().to_owned(),
+						attributes,
+						text: String::new(),
+					});
+					unknown.len() - 1
+				});
+				children.push(index);
+			},
+			XmlEvent::EndElement { .. } => match children.pop() {
+				None => return Ok(text),
+				Some(index) => {
+					if let (Some(i), Some(unknown)) = (index, unknown.as_deref_mut()) {
+						unknown[i].text = Whitespace::Collapse.apply(&unknown[i].text);
+					}
+					path.pop();
+				},
+			},
+			e @ XmlEvent::EndDocument => return Err(ParseError::unexpected(parser, path, &e)),
+			_ => (),
+		}
+	}
+}
+
+// Tekst elementa sa sažetim razmacima, element se zatvara
+pub fn read_string<R: Read>(parser: &mut Reader<R>, path: &mut Path) -> Result<String, ParseError> {
+	let text = read_text(parser, path, None)?;
+	path.pop();
+
+	Ok(Whitespace::Collapse.apply(&text))
+}
+
+const HRFISK20_PATH: &str = "/Invoice/ext:UBLExtensions/ext:UBLExtension/ext:ExtensionContent/hrextac:HRFISK20Data";
+
+const SIGNATURE_PATH: &str = "/Invoice/ext:UBLExtensions/ext:UBLExtension/ext:ExtensionContent/sig:UBLDocumentSignatures/sac:SignatureInformation/ds:Signature";
+
+// Sadržaj ext:UBLExtensions, poziva se nakon početka tog elementa i čita do njegova kraja
+fn parse_ubl_extensions<R: Read>(
+	parser: &mut Reader<R>,
+	xml_data: &mut XmlData,
+	path: &mut Path,
+	lossless: bool,
+) -> Result<(), ParseError> {
+	let mut uri = None;
+	loop {
+		let e = next_event(parser, path)?;
+		match &e {
+			XmlEvent::StartElement { name, attributes, .. } => {
+				let element = path.push(name);
+
+				if path.as_str().starts_with(SIGNATURE_PATH) {
+					parse_signature_element(parser, xml_data, path)?;
+					continue;
+				}
+
+				match path.as_str() {
+					"/Invoice/ext:UBLExtensions/ext:UBLExtension" => uri = None,
+					"/Invoice/ext:UBLExtensions/ext:UBLExtension/ext:ExtensionContent/sig:UBLDocumentSignatures" => (),
+					"/Invoice/ext:UBLExtensions/ext:UBLExtension/ext:ExtensionURI" => {
+						uri = Some(read_string(parser, path)?)
+					},
+					_ if element == Element::Mapped => {
+						read_mapped(parser, xml_data, path, attributes, lossless)?;
+					},
+					// Imena elemenata HrExtension nisu provjerena prema shemi, pa se ostali čuvaju kao izvorni XML
+					_ if element == Element::Unknown && parent(path) == Some(HRFISK20_PATH) => {
+						let xml = read_raw_xml(parser, path, &e)?;
+						path.pop();
+						if let Some(hr_extension) = &mut xml_data.hr_extension {
+							hr_extension.unknown.push(xml);
+						}
+					},
+					_ if parent(path) == Some("/Invoice/ext:UBLExtensions/ext:UBLExtension/ext:ExtensionContent") => {
+						let xml = read_raw_xml(parser, path, &e)?;
+						path.pop();
+						xml_data.extensions.push(RawExtension { uri: uri.take(), xml });
+					},
+					_ => (),
+				}
+			},
+			XmlEvent::EndElement { name } => {
+				path.pop();
+				if namespace::is(name, EXT, "UBLExtensions") {
+					return Ok(());
+				}
+			},
+			XmlEvent::EndDocument => return Err(ParseError::unexpected(parser, path, &e)),
+			_ => (),
+		}
+	}
+}
+
+// Putanja roditelja trenutnog elementa
+fn parent(path: &Path) -> Option<&str> {
+	path.as_str().rsplit_once('/').map(|(parent, _)| parent)
+}
+
+pub fn parse_signature_element<R: Read>(
+	parser: &mut Reader<R>,
+	xml_data: &mut XmlData,
+	path: &mut Path,
+) -> Result<(), ParseError> {
+	match &path.as_str()[SIGNATURE_PATH.len()..] {
+		"" => {
+			xml_data.signature();
+		},
+		"/ds:KeyInfo/ds:X509Data/ds:X509Certificate" => {
+			let value = read_string(parser, path)?;
+			xml_data.signature().set_certificate(&value);
+		},
+		"/ds:KeyInfo/ds:X509Data/ds:X509SubjectName" => {
+			let value = read_string(parser, path)?;
+			xml_data.signature().set_subject(value);
+		},
+		"/ds:Object/xades:QualifyingProperties/xades:SignedProperties/xades:SignedSignatureProperties/xades:SigningTime" =>
+		{
+			xml_data.signature().signing_time = Some(read_string(parser, path)?);
+		},
+		"/ds:Object/xades:QualifyingProperties/xades:SignedProperties/xades:SignedSignatureProperties/xades:SigningCertificate/xades:Cert/xades:IssuerSerial/ds:X509IssuerName" =>
+		{
+			let value = read_string(parser, path)?;
+			xml_data.signature().set_issuer(value);
+		},
+		"/ds:Object/xades:QualifyingProperties/xades:SignedProperties/xades:SignedSignatureProperties/xades:SigningCertificate/xades:Cert/xades:IssuerSerial/ds:X509SerialNumber" =>
+		{
+			let value = read_string(parser, path)?;
+			xml_data.signature().set_serial_number(value);
+		},
+		_ => (),
+	}
+
+	Ok(())
+}
+
+// Čita cijeli element, uključujući i početni, kao izvorni XML
+pub fn read_raw_xml<R: Read>(parser: &mut Reader<R>, path: &Path, start: &XmlEvent) -> Result<String, ParseError> {
+	let mut writer = EmitterConfig::new()
+		.write_document_declaration(false)
+		.create_writer(Vec::new());
+
+	let mut e = start.clone();
+	let mut depth = 0;
+	loop {
+		match e {
+			XmlEvent::StartElement { .. } => depth += 1,
+			XmlEvent::EndElement { .. } => depth -= 1,
+			XmlEvent::EndDocument => return Err(ParseError::unexpected(parser, path, &e)),
+			_ => (),
+		}
+		if let Some(w) = e.as_writer_event() {
+			writer
+				.write(w)
+				.map_err(|x| ParseError::new(parser, path, ParseErrorKind::UnexpectedEvent(x.to_string())))?;
+		}
+		if depth == 0 {
+			return Ok(String::from_utf8_lossy(&writer.into_inner()).into_owned());
+		}
+
+		e = next_event(parser, path)?;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::ParseOptions;
+	use xml::reader::XmlEvent;
+
+	fn lossless(xml: &str) -> crate::XmlData {
+		crate::parse_invoice_with(xml.as_bytes(), &ParseOptions { lossless: true, ..Default::default() }).unwrap()
+	}
+
+	#[test]
+	fn value_attributes_not_unknown() {
+		let xml_data = lossless(include_str!("../tests/data/invoice.xml"));
+		let names: Vec<&str> = xml_data
+			.unknown
+			.iter()
+			.flat_map(|x| &x.attributes)
+			.map(|(name, _)| name.as_str())
+			.collect();
+		assert!(!names.contains(&"currencyID"), "{:?}", names);
+		assert!(!names.contains(&"unitCode"), "{:?}", names);
+	}
+
+	const INVOICE: &str = include_str!("../tests/data/invoice.xml");
+
+	// Podelement vrijednosti nije dio teksta, a uz lossless je nepoznati element
+	#[test]
+	fn child_element_in_value() {
+		let xml = INVOICE.replace(
+			"<cbc:ID>1-POSL1-1</cbc:ID>",
+			r#"<cbc:ID xmlns:x="urn:example">1<x:b a="1">2<x:c>4</x:c></x:b>3</cbc:ID>"#,
+		);
+		assert_eq!(crate::from_str(&xml).unwrap().id, "13");
+
+		let xml_data = lossless(&xml);
+		assert_eq!(xml_data.id, "13");
+		let unknown: Vec<(&str, &str)> = xml_data
+			.unknown
+			.iter()
+			.map(|x| (x.path.as_str(), x.text.as_str()))
+			.collect();
+		assert!(unknown.contains(&("/Invoice/cbc:ID/{urn:example}b", "2")), "{:?}", unknown);
+		assert!(unknown.contains(&("/Invoice/cbc:ID/{urn:example}b/{urn:example}c", "4")), "{:?}", unknown);
+		let b = xml_data
+			.unknown
+			.iter()
+			.find(|x| x.path == "/Invoice/cbc:ID/{urn:example}b")
+			.unwrap();
+		assert_eq!(b.attributes, [("a".to_owned(), "1".to_owned())]);
+	}
+
+	#[test]
+	fn empty_notes_dropped() {
+		let xml = INVOICE
+			.replace("<cbc:DocumentCurrencyCode>", "<cbc:Note/><cbc:Note>  \n </cbc:Note><cbc:DocumentCurrencyCode>");
+		assert_eq!(crate::from_str(&xml).unwrap().notes, ["Napomena\ndruga linija"]);
+	}
+
+	#[test]
+	fn cdata_and_entities() {
+		let xml = INVOICE.replace(
+			"<cbc:Note>Napomena\ndruga linija</cbc:Note>",
+			"<cbc:Note><![CDATA[a <b> & c]]></cbc:Note><cbc:Note>x &amp; y &lt;z&gt; &#382;</cbc:Note>\
+			 <cbc:Note>prije <![CDATA[<i>]]> poslije</cbc:Note>",
+		);
+		assert_eq!(crate::from_str(&xml).unwrap().notes, ["a <b> & c", "x & y <z> ž", "prije <i> poslije"]);
+	}
+
+	// Nepoznati element i atribut poznatog elementa
+	fn with_unknown() -> String {
+		INVOICE
+			.replace("<cbc:DueDate>", r#"<cbc:DueDate xmlns:x="urn:example" x:a="1" b="2">"#)
+			.replace(
+				"<cbc:DocumentCurrencyCode>",
+				r#"<x:Extra xmlns:x="urn:example" c="3">tekst <x:Inner>unutra</x:Inner></x:Extra><cbc:DocumentCurrencyCode>"#,
+			)
+	}
+
+	#[test]
+	fn lossless_keeps_unknown() {
+		let xml_data = lossless(&with_unknown());
+		let unknown: Vec<String> = xml_data
+			.unknown
+			.iter()
+			.filter(|x| !x.path.contains("cac:TaxScheme"))
+			.map(|x| std::format!("{} {:?} {}", x.path, x.attributes, x.text))
+			.collect();
+		assert_eq!(
+			unknown,
+			[
+				r#"/Invoice/cbc:DueDate [("{urn:example}a", "1"), ("b", "2")] "#,
+				r#"/Invoice/{urn:example}Extra [("c", "3")] tekst"#,
+				r#"/Invoice/{urn:example}Extra/{urn:example}Inner [] unutra"#,
+			]
+		);
+		assert_eq!(xml_data.due_date.unwrap().to_string(), "2025-02-14");
+	}
+
+	#[test]
+	fn unknown_dropped_without_lossless() {
+		let xml_data = crate::from_str(&with_unknown()).unwrap();
+		assert!(xml_data.unknown.is_empty());
+		assert_eq!(xml_data.document_currency_code, "EUR");
+	}
+
+	// Događaji bez deklaracija prostora imena, koje izvorni XML može nasljeđivati od predaka
+	fn events(xml: &str) -> Vec<String> {
+		xml::EventReader::new(xml.as_bytes())
+			.into_iter()
+			.filter_map(|e| match e.unwrap() {
+				XmlEvent::StartElement { name, attributes, .. } => {
+					let attributes: Vec<String> = attributes
+						.iter()
+						.map(|x| std::format!("{}={}", x.name, x.value))
+						.collect();
+					Some(std::format!("<{:?}:{} {:?}>", name.namespace, name.local_name, attributes))
+				},
+				XmlEvent::EndElement { name } => Some(std::format!("</{:?}:{}>", name.namespace, name.local_name)),
+				XmlEvent::Characters(text) => Some(text),
+				_ => None,
+			})
+			.collect()
+	}
+
+	#[test]
+	fn raw_extension_round_trip() {
+		for options in [
+			ParseOptions::default(),
+			ParseOptions { lossless: true, ..Default::default() },
+		] {
+			let xml_data = crate::parse_invoice_with(INVOICE.as_bytes(), &options).unwrap();
+			let [extension] = xml_data.extensions.as_slice() else {
+				panic!("{:?}", xml_data.extensions)
+			};
+			assert_eq!(extension.uri.as_deref(), Some("urn:example:other"));
+			assert_eq!(events(&extension.xml), events(r#"<foo xmlns="urn:example:foo"><bar a="1">text</bar></foo>"#));
+		}
+	}
+}
@@ -0,0 +1,259 @@
+// Praćenje putanje trenutnog elementa tijekom parsiranja
+//
+// Putanja se drži u jednom međuspremniku koji se pri ulasku u element produljuje, a pri izlasku skraćuje.
+// Uz nju se prati čvor u stablu putanja izgrađenom iz tablice polja, pa se za elemente koji nisu u tablici
+// ne radi nikakvo uspoređivanje. Nakon zagrijavanja ni jedno ni drugo ne alocira.
+
+use crate::{
+	invoice::XmlData,
+	mapping::{Field, Mapped},
+	namespace,
+};
+use std::sync::LazyLock;
+use xml::{attribute::OwnedAttribute, name::OwnedName};
+
+struct Node {
+	// Kanonski prefiks, prazan za prostor imena Invoice
+	prefix: &'static str,
+	local_name: &'static str,
+	children: Vec<usize>,
+	// Polja koja počinju ovim elementom, kao vrijednost ili kao grupa. Svako je zadano nizom indeksa u tablicama
+	// polja od korijena do samog polja, pa se vrijednost upisuje bez uspoređivanja putanja.
+	routes: Vec<Vec<usize>>,
+	// Atributi elementa koji su u tablici polja
+	attributes: Vec<&'static str>,
+}
+
+// Odnos elementa prema tablici polja
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Element {
+	Mapped,
+	// Nije polje, ali se nalazi na putanji do nekog polja, npr. cac:Party
+	Structural,
+	Unknown,
+}
+
+// Stablo svih putanja iz tablice polja, korijen je Invoice
+struct Trie {
+	nodes: Vec<Node>,
+}
+
+static TRIE: LazyLock<Trie> = LazyLock::new(|| {
+	let mut trie = Trie {
+		nodes: vec![Node {
+			prefix: "",
+			local_name: "Invoice",
+			children: Vec::new(),
+			routes: Vec::new(),
+			attributes: Vec::new(),
+		}],
+	};
+	trie.insert_fields(0, XmlData::FIELDS, &[]);
+	trie
+});
+
+impl Trie {
+	fn insert_fields(&mut self, parent: usize, fields: &'static [Field], route: &[usize]) {
+		for (i, field) in fields.iter().enumerate() {
+			let mut node = parent;
+			for segment in field.path.split('/') {
+				if let Some(attribute) = segment.strip_prefix('@') {
+					self.nodes[node].attributes.push(attribute);
+					continue;
+				}
+				let (prefix, local_name) = segment.split_once(':').unwrap_or(("", segment));
+				node = self.child_or_insert(node, prefix, local_name);
+			}
+			self.nodes[node].attributes.extend(field.attributes);
+			let route = [route, &[i]].concat();
+			self.nodes[node].routes.push(route.clone());
+			self.insert_fields(node, field.children, &route);
+		}
+	}
+
+	fn child_or_insert(&mut self, parent: usize, prefix: &'static str, local_name: &'static str) -> usize {
+		if let Some(child) = self.find(parent, prefix, local_name) {
+			return child;
+		}
+
+		self.nodes.push(Node {
+			prefix,
+			local_name,
+			children: Vec::new(),
+			routes: Vec::new(),
+			attributes: Vec::new(),
+		});
+		let child = self.nodes.len() - 1;
+		self.nodes[parent].children.push(child);
+		child
+	}
+
+	fn find(&self, parent: usize, prefix: &str, local_name: &str) -> Option<usize> {
+		self.nodes[parent]
+			.children
+			.iter()
+			.copied()
+			.find(|&x| self.nodes[x].prefix == prefix && self.nodes[x].local_name == local_name)
+	}
+
+	fn child(&self, parent: usize, name: &OwnedName) -> Option<usize> {
+		let prefix = match name.namespace.as_deref() {
+			None | Some("") => "",
+			Some(uri) => namespace::canonical_prefix(uri)?,
+		};
+		self.find(parent, prefix, &name.local_name)
+	}
+}
+
+#[derive(Debug, Default)]
+pub struct Path {
+	buffer: String,
+	// Duljina međuspremnika prije ulaska u svaki otvoreni element
+	lengths: Vec<usize>,
+	// Čvor stabla za svaki otvoreni element, None ako putanja nije u tablici polja
+	nodes: Vec<Option<usize>>,
+}
+
+impl Path {
+	pub fn push(&mut self, name: &OwnedName) -> Element {
+		self.lengths.push(self.buffer.len());
+		self.buffer.push('/');
+		namespace::push_segment(&mut self.buffer, name);
+
+		let node = match self.nodes.last() {
+			Some(Some(parent)) => TRIE.child(*parent, name),
+			Some(None) => None,
+			None => namespace::is(name, namespace::INVOICE, "Invoice").then_some(0),
+		};
+		self.nodes.push(node);
+
+		match node {
+			Some(x) if !TRIE.nodes[x].routes.is_empty() => Element::Mapped,
+			Some(_) => Element::Structural,
+			None => Element::Unknown,
+		}
+	}
+
+	// Atributi trenutnog elementa koji nisu u tablici polja
+	pub fn unmapped_attributes<'a>(
+		&self,
+		attributes: &'a [OwnedAttribute],
+	) -> impl Iterator<Item = &'a OwnedAttribute> {
+		let mapped = match self.nodes.last() {
+			Some(Some(node)) => TRIE.nodes[*node].attributes.as_slice(),
+			_ => &[],
+		};
+		attributes
+			.iter()
+			.filter(move |x| x.name.namespace.is_some() || !mapped.contains(&x.name.local_name.as_str()))
+	}
+
+	// Polja trenutnog elementa, za Mapped::start i Mapped::text
+	pub fn routes(&self) -> &'static [Vec<usize>] {
+		match self.nodes.last() {
+			Some(Some(node)) => &TRIE.nodes[*node].routes,
+			_ => &[],
+		}
+	}
+
+	pub fn pop(&mut self) {
+		if let Some(length) = self.lengths.pop() {
+			self.buffer.truncate(length);
+			self.nodes.pop();
+		}
+	}
+
+	pub fn as_str(&self) -> &str {
+		&self.buffer
+	}
+
+	// Putanja relativna korijenskom elementu, kakva se koristi u tablici polja
+	pub fn relative(&self) -> &str {
+		self.buffer
+			.strip_prefix('/')
+			.and_then(|x| x.split_once('/'))
+			.map_or("", |(_, rest)| rest)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::namespace::{CAC, CBC, INVOICE};
+
+	fn name(namespace: &str, local_name: &str) -> OwnedName {
+		OwnedName::qualified(local_name, namespace, Some("x"))
+	}
+
+	#[test]
+	fn push_and_pop() {
+		let mut path = Path::default();
+		assert_eq!(path.push(&name(INVOICE, "Invoice")), Element::Structural);
+		assert_eq!(path.as_str(), "/Invoice");
+		assert_eq!(path.relative(), "");
+
+		let lengths: Vec<usize> = [
+			name(CAC, "InvoiceLine"),
+			name(CAC, "Item"),
+			name("urn:example", "Extra"),
+		]
+		.iter()
+		.map(|x| {
+			let length = path.as_str().len();
+			path.push(x);
+			length
+		})
+		.collect();
+		assert_eq!(path.as_str(), "/Invoice/cac:InvoiceLine/cac:Item/{urn:example}Extra");
+		assert_eq!(path.relative(), "cac:InvoiceLine/cac:Item/{urn:example}Extra");
+
+		for length in lengths.into_iter().rev() {
+			path.pop();
+			assert_eq!(path.as_str().len(), length);
+		}
+		assert_eq!(path.as_str(), "/Invoice");
+		path.pop();
+		path.pop();
+		assert_eq!(path.as_str(), "");
+	}
+
+	#[test]
+	fn trie() {
+		let mut path = Path::default();
+		path.push(&name(INVOICE, "Invoice"));
+		assert_eq!(path.push(&name(CAC, "AccountingSupplierParty")), Element::Mapped);
+		assert_eq!(path.push(&name(CAC, "Party")), Element::Structural);
+		assert!(path.routes().is_empty());
+
+		// Vrijednost i atribut schemeID su dva polja istog elementa
+		assert_eq!(path.push(&name(CBC, "EndpointID")), Element::Mapped);
+		assert_eq!(path.routes().len(), 2);
+		let attributes = [
+			OwnedAttribute::new(OwnedName::local("schemeID"), "9934"),
+			OwnedAttribute::new(OwnedName::local("other"), "1"),
+			OwnedAttribute::new(name("urn:example", "schemeID"), "1"),
+		];
+		let unmapped: Vec<String> = path
+			.unmapped_attributes(&attributes)
+			.map(|x| x.name.to_string())
+			.collect();
+		assert_eq!(unmapped, ["other", "{urn:example}x:schemeID"]);
+		path.pop();
+
+		// Isto lokalno ime iz drugog prostora imena nije polje
+		assert_eq!(path.push(&name("urn:example", "EndpointID")), Element::Unknown);
+		assert!(path.routes().is_empty());
+		assert_eq!(path.push(&name(CBC, "ID")), Element::Unknown);
+		path.pop();
+		path.pop();
+		assert_eq!(path.push(&name(CAC, "EndpointID")), Element::Unknown);
+	}
+
+	// Korijen mora biti Invoice iz prostora imena računa
+	#[test]
+	fn root() {
+		let mut path = Path::default();
+		assert_eq!(path.push(&name("urn:example", "Invoice")), Element::Unknown);
+		assert_eq!(path.push(&name(CBC, "ID")), Element::Unknown);
+	}
+}
@@ -0,0 +1,401 @@
+// Čitač XML događaja koji provodi ograničenja iz Limits
+//
+// Sve funkcije parsera čitaju događaje kroz njega, pa se ograničenja provjeravaju na jednom mjestu.
+
+use crate::{
+	ParseError, ParseErrorKind,
+	limits::{Limit, Limits},
+	namespace::{self, CAC, CBC},
+	path::Path,
+};
+use std::io::{self, BufReader, Read};
+use xml::{
+	EventReader, ParserConfig,
+	common::{Position, TextPosition},
+	reader::XmlEvent,
+};
+
+// Ulaz koji sam prati ograničenja koja xml-rs provodi tek nakon što je tekst ili atribute već spremio
+//
+// Bajtovi do prvog koji prelazi ograničenje predaju se xml-rs, a greška se javlja pri sljedećem čitanju, pa
+// xml-rs prije nje vrati sve događaje do mjesta prekoračenja. xml-rs ne javlja koje je svoje ograničenje
+// prekoračio, pa se Scanner provjerava prije njega: broji bajtove prije dekodiranja entiteta, kojih nikad nije
+// manje od znakova koje broji xml-rs.
+struct Bounded<R> {
+	inner: R,
+	remaining: u64,
+	scanner: Scanner,
+	exceeded: Option<Limit>,
+}
+
+impl<R: Read> Read for Bounded<R> {
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		if self.exceeded.is_some() {
+			return Err(io::Error::other("prekoračeno ograničenje"));
+		}
+		if self.remaining == 0 {
+			// Jedan bajt više od ograničenja znači da je dokument prevelik
+			let mut byte = [0];
+			if self.inner.read(&mut byte)? == 0 {
+				return Ok(0);
+			}
+			self.exceeded = Some(Limit::DocumentSize);
+			return Err(io::Error::other("dokument je prevelik"));
+		}
+
+		let length = buf.len().min(usize::try_from(self.remaining).unwrap_or(usize::MAX));
+		let mut n = self.inner.read(&mut buf[..length])?;
+		if let Err((i, limit)) = self.scanner.scan(&buf[..n]) {
+			self.exceeded = Some(limit);
+			if i == 0 {
+				return Err(io::Error::other("prekoračeno ograničenje"));
+			}
+			n = i;
+		}
+		self.remaining -= n as u64;
+		Ok(n)
+	}
+}
+
+// Dio dokumenta u kojem je trenutni bajt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Markup {
+	Text,
+	// Nakon <
+	Open,
+	// Nakon <!
+	Bang,
+	// Početna ili završna oznaka, s navodnikom vrijednosti atributa u kojoj je bajt
+	Tag(Option<u8>),
+	Comment,
+	CData,
+	Instruction,
+	// DOCTYPE i slično, odbija se u Reader::next
+	Declaration,
+}
+
+// Prati duljinu teksta elementa, broj i duljinu atributa na razini bajtova
+struct Scanner {
+	markup: Markup,
+	// Posljednja dva bajta, za kraj komentara (-->), CDATA (]]>) i instrukcije (?>)
+	last: [u8; 2],
+	// Tekst od posljednje oznake, uključujući komentare i CDATA
+	text: usize,
+	attributes: usize,
+	value: usize,
+	// Početak imena posljednjeg atributa, deklaracije prostora imena se ne broje kao atributi
+	name: [u8; 6],
+	name_length: usize,
+	name_done: bool,
+	max_text: usize,
+	max_attributes: usize,
+	max_value: usize,
+}
+
+impl Scanner {
+	fn new(limits: &Limits) -> Self {
+		Self {
+			markup: Markup::Text,
+			last: [0; 2],
+			text: 0,
+			attributes: 0,
+			value: 0,
+			name: [0; 6],
+			name_length: 0,
+			name_done: false,
+			// Scanner ne zna je li element privitak, pa vrijedi veće ograničenje, a točno provjerava Reader::next
+			max_text: limits.max_text_length.max(limits.max_attachment_size),
+			max_attributes: limits.max_attributes,
+			max_value: limits.max_text_length,
+		}
+	}
+
+	// Err s položajem prvog bajta koji prelazi ograničenje
+	fn scan(&mut self, bytes: &[u8]) -> Result<(), (usize, Limit)> {
+		for (i, &b) in bytes.iter().enumerate() {
+			let previous = self.markup;
+			self.markup = match (self.markup, b) {
+				(Markup::Text, b'<') => Markup::Open,
+				(Markup::Open, b'!') => Markup::Bang,
+				(Markup::Open, b'?') => Markup::Instruction,
+				(Markup::Open, _) => {
+					self.text = 0;
+					self.attributes = 0;
+					self.name_length = 0;
+					Markup::Tag(None)
+				},
+				(Markup::Bang, b'-') => Markup::Comment,
+				(Markup::Bang, b'[') => Markup::CData,
+				(Markup::Bang, _) => Markup::Declaration,
+				(Markup::Tag(None), b'>') => Markup::Text,
+				(Markup::Tag(None), b'"' | b'\'') => {
+					self.value = 0;
+					Markup::Tag(Some(b))
+				},
+				(Markup::Tag(None), b'=') => {
+					if !self.is_namespace_declaration() {
+						self.attributes += 1;
+					}
+					Markup::Tag(None)
+				},
+				(Markup::Tag(None), _) => {
+					self.name_byte(b);
+					Markup::Tag(None)
+				},
+				(Markup::Tag(Some(quote)), _) if b == quote => {
+					self.name_done = true;
+					Markup::Tag(None)
+				},
+				(Markup::Tag(Some(quote)), _) => {
+					self.value += 1;
+					Markup::Tag(Some(quote))
+				},
+				(Markup::Comment, b'>') if self.last == *b"--" => Markup::Text,
+				(Markup::CData, b'>') if self.last == *b"]]" => Markup::Text,
+				(Markup::Instruction, b'>') if self.last[1] == b'?' => Markup::Text,
+				(Markup::Declaration, b'>') => Markup::Text,
+				(markup, _) => markup,
+			};
+			self.last = [self.last[1], b];
+
+			// Znakovi koji otvaraju ili zatvaraju oznaku nisu tekst
+			if self.markup == previous
+				&& matches!(self.markup, Markup::Text | Markup::Comment | Markup::CData | Markup::Instruction)
+			{
+				self.text += 1;
+			}
+			if self.text > self.max_text || self.value > self.max_value {
+				return Err((i, Limit::TextLength));
+			}
+			if self.attributes > self.max_attributes {
+				return Err((i, Limit::Attributes));
+			}
+		}
+		Ok(())
+	}
+
+	fn name_byte(&mut self, b: u8) {
+		if b.is_ascii_whitespace() {
+			self.name_done = true;
+			return;
+		}
+		if self.name_done {
+			self.name_length = 0;
+			self.name_done = false;
+		}
+		if let Some(x) = self.name.get_mut(self.name_length) {
+			*x = b;
+		}
+		self.name_length += 1;
+	}
+
+	// xmlns="..." ili xmlns:prefiks="..."
+	fn is_namespace_declaration(&self) -> bool {
+		match self.name_length {
+			5 => self.name[..5] == *b"xmlns",
+			6.. => self.name == *b"xmlns:",
+			_ => false,
+		}
+	}
+}
+
+pub struct Reader<R: Read> {
+	events: EventReader<BufReader<Bounded<R>>>,
+	limits: Limits,
+	depth: usize,
+	invoice_lines: usize,
+	// Duljina teksta trenutnog elementa
+	text_length: usize,
+	// Trenutni element je privitak, za njega vrijedi max_attachment_size
+	attachment: bool,
+}
+
+impl<R: Read> Reader<R> {
+	pub fn new(reader: R, limits: &Limits) -> Self {
+		let config = ParserConfig::new()
+			// Prilagođeni entiteti se ne proširuju, a DTD se odbija u next
+			.max_entity_expansion_depth(0)
+			.max_entity_expansion_length(0)
+			// Atribute i tekst xml-rs drži u memoriji prije nego što vrati događaj, pa ih mora ograničiti i on
+			.max_attributes(limits.max_attributes)
+			.max_attribute_length(limits.max_text_length)
+			.max_data_length(limits.max_text_length.max(limits.max_attachment_size));
+		let input = Bounded {
+			inner: reader,
+			remaining: limits.max_document_size,
+			scanner: Scanner::new(limits),
+			exceeded: None,
+		};
+
+		Self {
+			events: EventReader::new_with_config(BufReader::new(input), config),
+			limits: limits.clone(),
+			depth: 0,
+			invoice_lines: 0,
+			text_length: 0,
+			attachment: false,
+		}
+	}
+
+	pub fn next(&mut self, path: &Path) -> Result<XmlEvent, ParseError> {
+		let e = match self.events.next() {
+			Ok(e) => e,
+			Err(e) => match self.events.source().get_ref().exceeded {
+				Some(limit) => return Err(self.input_limit_exceeded(path, limit)),
+				None => return Err(ParseError::malformed(path, e)),
+			},
+		};
+
+		match &e {
+			XmlEvent::Doctype { .. } => return Err(ParseError::new(self, path, ParseErrorKind::DoctypeNotAllowed)),
+			XmlEvent::StartElement { name, attributes, .. } => {
+				self.depth += 1;
+				self.text_length = 0;
+				self.attachment = namespace::is(name, CBC, "EmbeddedDocumentBinaryObject");
+				if self.depth > self.limits.max_depth {
+					return Err(self.limit_exceeded(path, Limit::Depth, self.limits.max_depth as u64));
+				}
+				if attributes.len() > self.limits.max_attributes {
+					return Err(self.limit_exceeded(path, Limit::Attributes, self.limits.max_attributes as u64));
+				}
+				if self.depth == 2 && namespace::is(name, CAC, "InvoiceLine") {
+					self.invoice_lines += 1;
+					if self.invoice_lines > self.limits.max_invoice_lines {
+						return Err(self.limit_exceeded(
+							path,
+							Limit::InvoiceLines,
+							self.limits.max_invoice_lines as u64,
+						));
+					}
+				}
+			},
+			XmlEvent::EndElement { .. } => {
+				self.depth -= 1;
+				self.text_length = 0;
+				self.attachment = false;
+			},
+			XmlEvent::Characters(chars) | XmlEvent::CData(chars) | XmlEvent::Whitespace(chars) => {
+				self.text_length += chars.len();
+				let (limit, max) = match self.attachment {
+					true => (Limit::AttachmentSize, self.limits.max_attachment_size),
+					false => (Limit::TextLength, self.limits.max_text_length),
+				};
+				if self.text_length > max {
+					return Err(self.limit_exceeded(path, limit, max as u64));
+				}
+			},
+			_ => (),
+		}
+
+		Ok(e)
+	}
+
+	// Ograničenje koje je prekoračeno već pri čitanju ulaza, vidi Bounded
+	fn input_limit_exceeded(&self, path: &Path, limit: Limit) -> ParseError {
+		match limit {
+			Limit::DocumentSize => self.limit_exceeded(path, limit, self.limits.max_document_size),
+			Limit::Attributes => self.limit_exceeded(path, limit, self.limits.max_attributes as u64),
+			_ if self.attachment => {
+				self.limit_exceeded(path, Limit::AttachmentSize, self.limits.max_attachment_size as u64)
+			},
+			_ => self.limit_exceeded(path, Limit::TextLength, self.limits.max_text_length as u64),
+		}
+	}
+
+	fn limit_exceeded(&self, path: &Path, limit: Limit, max: u64) -> ParseError {
+		ParseError::new(self, path, ParseErrorKind::LimitExceeded { limit, max })
+	}
+}
+
+impl<R: Read> Position for Reader<R> {
+	fn position(&self) -> TextPosition {
+		self.events.position()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	// Svi događaji dokumenta, Err s prekoračenim ograničenjem i putanjom na kojoj je prekoračeno
+	fn read(document: &str, limits: &Limits) -> Result<(), (Limit, String)> {
+		let mut reader = Reader::new(document.as_bytes(), limits);
+		let mut path = Path::default();
+		loop {
+			match reader.next(&path) {
+				Ok(XmlEvent::StartElement { name, .. }) => {
+					path.push(&name);
+				},
+				Ok(XmlEvent::EndElement { .. }) => path.pop(),
+				Ok(XmlEvent::EndDocument) => return Ok(()),
+				Ok(_) => (),
+				Err(ParseError { kind: ParseErrorKind::LimitExceeded { limit, .. }, path, .. }) => {
+					return Err((limit, path));
+				},
+				Err(e) => panic!("{}", e),
+			}
+		}
+	}
+
+	#[test]
+	fn text_length() {
+		let limits = Limits { max_text_length: 8, ..Limits::default() };
+		assert_eq!(
+			read("<r><a>12345678</a><b>123456789</b></r>", &limits),
+			Err((Limit::TextLength, "/r/b".to_owned()))
+		);
+
+		// CDATA se broji u tekst, a < u CDATA i > u atributu ne završavaju ih
+		let document = "<r><a x='>'><![CDATA[<1234]]><!-- x -->56789</a></r>";
+		assert_eq!(read(document, &limits), Err((Limit::TextLength, "/r/a".to_owned())));
+		assert_eq!(read(document, &Limits { max_text_length: 64, ..limits }), Ok(()));
+	}
+
+	#[test]
+	fn attachment_size() {
+		let limits = Limits {
+			max_text_length: 100,
+			max_attachment_size: 200,
+			..Limits::default()
+		};
+		let document = |length| {
+			std::format!(
+				"<r xmlns:cbc=\"{}\"><cbc:EmbeddedDocumentBinaryObject>{}</cbc:EmbeddedDocumentBinaryObject></r>",
+				CBC,
+				"A".repeat(length),
+			)
+		};
+		assert_eq!(read(&document(200), &limits), Ok(()));
+		assert_eq!(read(&document(201), &limits).unwrap_err().0, Limit::AttachmentSize);
+	}
+
+	#[test]
+	fn attributes() {
+		// Deklaracije prostora imena nisu atributi
+		let limits = Limits { max_attributes: 2, ..Limits::default() };
+		assert_eq!(read("<r xmlns='x' xmlns:x='x'><a b = '1' c='2'/></r>", &limits), Ok(()));
+		assert_eq!(read("<r><a b='1' c='2' d='3'/></r>", &limits), Err((Limit::Attributes, "/r".to_owned())));
+	}
+
+	#[test]
+	fn attribute_length() {
+		let limits = Limits { max_text_length: 4, ..Limits::default() };
+		assert_eq!(read("<r><a b='1234'/></r>", &limits), Ok(()));
+		assert_eq!(read("<r><a b='12345'/></r>", &limits).unwrap_err().0, Limit::TextLength);
+	}
+
+	#[test]
+	fn document_size() {
+		let document = "<r><a/></r>";
+		let limits = Limits {
+			max_document_size: document.len() as u64,
+			..Limits::default()
+		};
+		assert_eq!(read(document, &limits), Ok(()));
+		let limits = Limits {
+			max_document_size: document.len() as u64 - 1,
+			..Limits::default()
+		};
+		assert_eq!(read(document, &limits).unwrap_err().0, Limit::DocumentSize);
+	}
+}
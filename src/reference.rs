@@ -0,0 +1,343 @@
+// Poziv na broj po modelima HUB-a (HR00 do HR99)
+//
+// Zapis je model i poziv na broj, npr. HR01 1234-56-7890. Poziv na broj ima najviše 22 znaka, a čine ga
+// najviše tri podatka (P1-P2-P3) odvojena crticama, svaki od najviše 12 znamenki; samo HR00 dopušta četiri
+// podatka. Model određuje koji podaci završavaju kontrolnom znamenkom.
+//
+// Tablica RULES sadrži modele čija su pravila kontrolnih znamenki ugrađena. Za ostale modele iz popisa
+// HUB-a (UNCHECKED) i za nepoznate modele provjerava se samo oblik zapisa, a XmlData::invalid_payment_reference
+// ih označava kao neprovjerene (ReferenceError::Unchecked), kako se ne bi prikazali kao ispravni.
+
+use std::{
+	fmt::{self, Display},
+	str::FromStr,
+};
+
+use crate::oib;
+
+const MAX_LENGTH: usize = 22;
+const MAX_PART_LENGTH: usize = 12;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Scheme {
+	// MOD 11 INI: težine od 2 naviše zdesna, kontrolna znamenka je 11 - ostatak, 0 za ostatak 0 ili 1
+	Mod11Ini,
+	// ISO 7064 MOD 11,10, kao za OIB
+	Iso7064,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Checked {
+	// Redni broj podatka, od nule
+	Part(usize),
+	// Sve znamenke P1P2P3 bez crtica, kontrolna znamenka je zadnja znamenka poziva na broj
+	Whole,
+}
+
+struct Rule {
+	model: u8,
+	max_parts: usize,
+	checks: &'static [(Checked, Scheme)],
+}
+
+const RULES: &[Rule] = &[
+	// Bez kontrolnih znamenki, jedini model s četiri podatka
+	Rule { model: 0, max_parts: 4, checks: &[] },
+	Rule {
+		model: 1,
+		max_parts: 3,
+		checks: &[(Checked::Whole, Scheme::Mod11Ini)],
+	},
+	Rule {
+		model: 2,
+		max_parts: 3,
+		checks: &[(Checked::Part(1), Scheme::Mod11Ini)],
+	},
+	Rule {
+		model: 3,
+		max_parts: 3,
+		checks: &[
+			(Checked::Part(0), Scheme::Mod11Ini),
+			(Checked::Part(1), Scheme::Mod11Ini),
+			(Checked::Part(2), Scheme::Mod11Ini),
+		],
+	},
+	Rule {
+		model: 5,
+		max_parts: 3,
+		checks: &[(Checked::Part(0), Scheme::Mod11Ini)],
+	},
+	Rule { model: 9, max_parts: 3, checks: &[] },
+	Rule {
+		model: 11,
+		max_parts: 3,
+		checks: &[
+			(Checked::Part(0), Scheme::Mod11Ini),
+			(Checked::Part(1), Scheme::Mod11Ini),
+		],
+	},
+	Rule {
+		model: 12,
+		max_parts: 1,
+		checks: &[(Checked::Part(0), Scheme::Mod11Ini)],
+	},
+	// Porezna uprava, P1 je OIB
+	Rule {
+		model: 67,
+		max_parts: 3,
+		checks: &[(Checked::Part(0), Scheme::Iso7064)],
+	},
+	// Porezna uprava, P1 je vrsta prihoda, a P2 OIB
+	Rule {
+		model: 68,
+		max_parts: 3,
+		checks: &[
+			(Checked::Part(0), Scheme::Mod11Ini),
+			(Checked::Part(1), Scheme::Iso7064),
+		],
+	},
+	// Bez poziva na broj
+	Rule { model: 99, max_parts: 0, checks: &[] },
+];
+
+// Ostali modeli iz popisa HUB-a, čije se kontrolne znamenke još ne provjeravaju
+const UNCHECKED: &[u8] = &[
+	4, 6, 7, 8, 10, 13, 14, 15, 16, 17, 18, 23, 24, 26, 27, 28, 29, 30, 31, 33, 34, 40, 41, 42, 43, 55, 62, 63, 64, 65,
+	69, 83, 84,
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaymentReference {
+	model: u8,
+	parts: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReferenceError {
+	// Zapis ne počinje s HR i dvije znamenke
+	Model,
+	// Znak koji nije znamenka ili crtica, ili prazan podatak
+	Format,
+	Length(usize),
+	Parts { max: usize, found: usize },
+	PartLength { part: usize },
+	// Podatak P1, P2 ili P3 (od jedan), 0 za cijeli poziv na broj
+	ControlDigit { part: usize },
+	// Zapis je ispravnog oblika, ali se kontrolne znamenke modela ne provjeravaju; true za model iz popisa HUB-a
+	Unchecked { model: u8, known: bool },
+}
+
+impl Display for ReferenceError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Model => write!(f, "poziv na broj ne počinje modelom HR00 do HR99"),
+			Self::Format => write!(f, "poziv na broj smije sadržavati samo znamenke i crtice"),
+			Self::Length(length) => write!(f, "poziv na broj ima {} znakova, a najviše {}", length, MAX_LENGTH),
+			Self::Parts { max, found } => write!(f, "poziv na broj ima {} podataka, a model dopušta {}", found, max),
+			Self::PartLength { part } => write!(f, "podatak P{} ima više od {} znamenki", part, MAX_PART_LENGTH),
+			Self::ControlDigit { part: 0 } => write!(f, "kontrolna znamenka poziva na broj nije ispravna"),
+			Self::ControlDigit { part } => write!(f, "kontrolna znamenka podatka P{} nije ispravna", part),
+			Self::Unchecked { model, known: true } => {
+				write!(f, "kontrolne znamenke modela HR{:02} se ne provjeravaju", model)
+			},
+			Self::Unchecked { model, known: false } => write!(f, "model HR{:02} nije u popisu modela HUB-a", model),
+		}
+	}
+}
+
+impl std::error::Error for ReferenceError {}
+
+impl PaymentReference {
+	// Model, npr. HR01, koji se na uplatnici i u crtičnom kodu navodi odvojeno
+	pub fn model(&self) -> String {
+		std::format!("HR{:02}", self.model)
+	}
+
+	pub fn model_number(&self) -> u8 {
+		self.model
+	}
+
+	// Poziv na broj bez modela, npr. 1234-56-7890
+	pub fn reference(&self) -> String {
+		self.parts.join("-")
+	}
+
+	// Podaci P1, P2 i P3
+	pub fn parts(&self) -> &[String] {
+		&self.parts
+	}
+
+	// Model ima pravila kontrolnih znamenki, pa je poziv na broj provjeren u potpunosti
+	pub fn is_fully_checked(&self) -> bool {
+		rule(self.model).is_some()
+	}
+
+	// Razlog zbog kojeg poziv na broj nije provjeren u potpunosti
+	pub fn unchecked(&self) -> Option<ReferenceError> {
+		(!self.is_fully_checked())
+			.then(|| ReferenceError::Unchecked { model: self.model, known: UNCHECKED.contains(&self.model) })
+	}
+}
+
+fn rule(model: u8) -> Option<&'static Rule> {
+	RULES.iter().find(|x| x.model == model)
+}
+
+fn mod11ini(digits: &str) -> u32 {
+	let sum: u32 = digits
+		.bytes()
+		.rev()
+		.zip(2..)
+		.map(|(digit, weight)| u32::from(digit - b'0') * weight)
+		.sum();
+	match sum % 11 {
+		0 | 1 => 0,
+		remainder => 11 - remainder,
+	}
+}
+
+// Zadnja znamenka je kontrolna znamenka ostalih
+fn control_digit_valid(digits: &str, scheme: Scheme) -> bool {
+	let Some((body, check)) = digits.split_at_checked(digits.len().saturating_sub(1)) else {
+		return false;
+	};
+	if body.is_empty() {
+		return false;
+	}
+	let expected = match scheme {
+		Scheme::Mod11Ini => mod11ini(body),
+		Scheme::Iso7064 => u32::from(oib::check_digit(body.as_bytes())),
+	};
+	u32::from(check.as_bytes()[0] - b'0') == expected
+}
+
+// Prihvaća model i poziv na broj odvojene razmakom ili bez razmaka, npr. HR01 1234-56-7890 ili HR011234-56-7890
+impl FromStr for PaymentReference {
+	type Err = ReferenceError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let text = s.trim();
+		let model = text
+			.strip_prefix("HR")
+			.and_then(|x| x.get(..2))
+			.filter(|x| x.bytes().all(|x| x.is_ascii_digit()))
+			.ok_or(ReferenceError::Model)?;
+		let model: u8 = model.parse().map_err(|_| ReferenceError::Model)?;
+		let reference = text[4..].trim();
+
+		if reference.len() > MAX_LENGTH {
+			return Err(ReferenceError::Length(reference.len()));
+		}
+		let parts: Vec<String> = match reference.is_empty() {
+			true => Vec::new(),
+			false => reference.split('-').map(str::to_owned).collect(),
+		};
+		if parts
+			.iter()
+			.any(|x| x.is_empty() || !x.bytes().all(|x| x.is_ascii_digit()))
+		{
+			return Err(ReferenceError::Format);
+		}
+		if let Some(i) = parts.iter().position(|x| x.len() > MAX_PART_LENGTH) {
+			return Err(ReferenceError::PartLength { part: i + 1 });
+		}
+
+		let rule = rule(model);
+		let max = rule.map_or(3, |x| x.max_parts);
+		if parts.len() > max {
+			return Err(ReferenceError::Parts { max, found: parts.len() });
+		}
+		for (checked, scheme) in rule.map_or(&[][..], |x| x.checks) {
+			let (digits, part) = match *checked {
+				Checked::Whole => (parts.concat(), 0),
+				Checked::Part(i) => (parts.get(i).cloned().unwrap_or_default(), i + 1),
+			};
+			if !control_digit_valid(&digits, *scheme) {
+				return Err(ReferenceError::ControlDigit { part });
+			}
+		}
+
+		Ok(Self { model, parts })
+	}
+}
+
+impl Display for PaymentReference {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self.parts.is_empty() {
+			true => write!(f, "{}", self.model()),
+			false => write!(f, "{} {}", self.model(), self.reference()),
+		}
+	}
+}
+
+impl serde::Serialize for PaymentReference {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.collect_str(self)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn parse(text: &str) -> Result<PaymentReference, ReferenceError> {
+		text.parse()
+	}
+
+	#[test]
+	fn valid() {
+		let reference = parse("HR011234-56-7890").unwrap();
+		assert_eq!(reference.to_string(), "HR01 1234-56-7890");
+		assert_eq!(reference.parts(), ["1234", "56", "7890"]);
+		assert!(reference.is_fully_checked());
+
+		for text in [
+			"HR02 11-12343-5",
+			"HR05 558",
+			"HR68 8109-94577403194",
+			"HR67 94577403194",
+			"HR99",
+		] {
+			assert_eq!(parse(text).map(|x| x.unchecked()), Ok(None), "{}", text);
+		}
+	}
+
+	#[test]
+	fn control_digits() {
+		assert_eq!(parse("HR01 1234-56-7891"), Err(ReferenceError::ControlDigit { part: 0 }));
+		assert_eq!(parse("HR02 11-12344-5"), Err(ReferenceError::ControlDigit { part: 2 }));
+		assert_eq!(parse("HR68 8108-94577403194"), Err(ReferenceError::ControlDigit { part: 1 }));
+		assert_eq!(parse("HR68 8109-94577403195"), Err(ReferenceError::ControlDigit { part: 2 }));
+		// Podatak koji model provjerava, a nedostaje
+		assert_eq!(parse("HR68 8109"), Err(ReferenceError::ControlDigit { part: 2 }));
+	}
+
+	#[test]
+	fn parts() {
+		assert_eq!(parse("HR00 1-2-3-4").unwrap().parts().len(), 4);
+		assert_eq!(parse("HR00 1-2-3-4-5"), Err(ReferenceError::Parts { max: 4, found: 5 }));
+		assert_eq!(parse("HR01 1-2-3-4"), Err(ReferenceError::Parts { max: 3, found: 4 }));
+		assert_eq!(parse("HR99 1"), Err(ReferenceError::Parts { max: 0, found: 1 }));
+	}
+
+	#[test]
+	fn format() {
+		assert_eq!(parse("HR1"), Err(ReferenceError::Model));
+		assert_eq!(parse("SI01 1234"), Err(ReferenceError::Model));
+		assert_eq!(parse("HRAB 1234"), Err(ReferenceError::Model));
+		assert_eq!(parse("HR00 12a4"), Err(ReferenceError::Format));
+		assert_eq!(parse("HR00 12--4"), Err(ReferenceError::Format));
+		assert_eq!(parse("HR00 1234567890123"), Err(ReferenceError::PartLength { part: 1 }));
+		assert_eq!(parse("HR00 123456789012-1234567890"), Err(ReferenceError::Length(23)));
+	}
+
+	#[test]
+	fn unchecked_models() {
+		assert_eq!(parse("HR04 1234").unwrap().unchecked(), Some(ReferenceError::Unchecked { model: 4, known: true }));
+		assert_eq!(
+			parse("HR50 1234").unwrap().unchecked(),
+			Some(ReferenceError::Unchecked { model: 50, known: false })
+		);
+		assert!(!parse("HR50 1234").unwrap().is_fully_checked());
+	}
+}
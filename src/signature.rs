@@ -0,0 +1,92 @@
+// Podaci o elektroničkom potpisu (ds:Signature, XAdES)
+
+use crate::xmldsig::Verification;
+use base64::{Engine, engine::general_purpose::STANDARD};
+use time::{OffsetDateTime, format_description::well_known::Rfc3339};
+use x509_parser::prelude::{FromDer, X509Certificate};
+
+#[derive(Debug, Default, serde::Serialize)]
+pub struct SignatureInfo {
+	// Ime potpisnika (CN iz certifikata)
+	pub signer_name: Option<String>,
+	// Subjekt certifikata
+	// ds:KeyInfo/ds:X509Data/ds:X509Certificate ili ds:KeyInfo/ds:X509Data/ds:X509SubjectName
+	pub subject: Option<String>,
+	// Izdavatelj certifikata
+	// xades:SigningCertificate/xades:Cert/xades:IssuerSerial/ds:X509IssuerName
+	pub issuer: Option<String>,
+	// Serijski broj certifikata
+	// xades:SigningCertificate/xades:Cert/xades:IssuerSerial/ds:X509SerialNumber
+	pub serial_number: Option<String>,
+	// Certifikat vrijedi od
+	pub not_before: Option<String>,
+	// Certifikat vrijedi do
+	pub not_after: Option<String>,
+	// Vrijeme potpisivanja
+	// xades:SignedProperties/xades:SignedSignatureProperties/xades:SigningTime
+	pub signing_time: Option<String>,
+	// Certifikat potpisnika u DER obliku
+	#[serde(skip)]
+	pub certificate: Option<Vec<u8>>,
+	// Rezultat provjere potpisa, ako je zadan direktorij s pouzdanim certifikatima
+	pub verification: Option<Verification>,
+}
+
+impl SignatureInfo {
+	// Podatke iz certifikata smatramo točnijima od onih navedenih u XAdES svojstvima
+	// Potpisnik je prvi certifikat u ds:X509Data, ostali su certifikati lanca
+	pub fn set_certificate(&mut self, base64: &str) {
+		if self.certificate.is_some() {
+			return;
+		}
+		let base64: String = base64.chars().filter(|c| !c.is_whitespace()).collect();
+		let Ok(der) = STANDARD.decode(base64) else {
+			return;
+		};
+
+		if let Ok((_, cert)) = X509Certificate::from_der(&der) {
+			self.signer_name = cert
+				.subject()
+				.iter_common_name()
+				.next()
+				.and_then(|cn| cn.as_str().ok())
+				.map(str::to_owned);
+			self.subject = Some(cert.subject().to_string());
+			self.issuer = Some(cert.issuer().to_string());
+			self.serial_number = Some(cert.serial.to_string());
+			self.not_before = Some(format_time(cert.validity().not_before.to_datetime()));
+			self.not_after = Some(format_time(cert.validity().not_after.to_datetime()));
+		}
+
+		self.certificate = Some(der);
+	}
+
+	pub fn set_subject(&mut self, subject: String) {
+		self.subject.get_or_insert(subject);
+	}
+
+	pub fn set_issuer(&mut self, issuer: String) {
+		self.issuer.get_or_insert(issuer);
+	}
+
+	pub fn set_serial_number(&mut self, serial_number: String) {
+		self.serial_number.get_or_insert(serial_number);
+	}
+
+	// "Digitalno potpisao: … dana …", kao u FINA pregledniku
+	pub fn panel_text(&self) -> String {
+		let signer = self
+			.signer_name
+			.as_deref()
+			.or(self.subject.as_deref())
+			.unwrap_or("nepoznat potpisnik");
+		match &self.signing_time {
+			Some(time) => std::format!("Digitalno potpisao: {} dana {}", signer, time),
+			None => std::format!("Digitalno potpisao: {}", signer),
+		}
+	}
+}
+
+fn format_time(time: OffsetDateTime) -> String {
+	time.format(&Rfc3339).unwrap_or_default()
+}
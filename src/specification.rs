@@ -0,0 +1,211 @@
+// Identifikator specifikacije (BT-24) i vrsta poslovnog procesa (BT-23)
+
+pub const EN16931: &str = "urn:cen.eu:en16931:2017";
+pub const HR_CIUS: &str = "urn:cen.eu:en16931:2017#compliant#urn:mingo.hr:2018";
+pub const PEPPOL_BIS_3: &str = "urn:cen.eu:en16931:2017#compliant#urn:fdc:peppol.eu:2017:poacc:billing:3.0";
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub enum Specification {
+	// Osnovna europska norma bez dodatnih pravila
+	En16931,
+	// Hrvatska specifikacija osnovne upotrebe, npr. ":1.0" na kraju identifikatora
+	HrCius { version: Option<String> },
+	// PEPPOL BIS Billing 3.0
+	PeppolBis3,
+	// Identifikator koji ne prepoznajemo
+	Unknown(String),
+}
+
+impl Specification {
+	pub fn parse(customization_id: &str) -> Self {
+		let id = customization_id.trim();
+		if id == EN16931 {
+			return Self::En16931;
+		}
+
+		if let Some(rest) = id.strip_prefix(HR_CIUS) {
+			let version = rest.strip_prefix(':').filter(|v| !v.is_empty()).map(str::to_owned);
+			if version.is_some() || rest.is_empty() {
+				return Self::HrCius { version };
+			}
+		}
+
+		if id == PEPPOL_BIS_3 {
+			return Self::PeppolBis3;
+		}
+
+		Self::Unknown(id.to_owned())
+	}
+}
+
+// Poslovni procesi P1..P12 iz FINA specifikacije osnovne upotrebe (poglavlje 3.2)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub enum BusinessProcess {
+	P1,
+	P2,
+	P3,
+	P4,
+	P5,
+	P6,
+	P7,
+	P8,
+	P9,
+	P10,
+	P11,
+	P12,
+}
+
+impl BusinessProcess {
+	pub fn parse(profile_id: &str) -> Option<Self> {
+		let process = match profile_id.trim() {
+			"P1" => Self::P1,
+			"P2" => Self::P2,
+			"P3" => Self::P3,
+			"P4" => Self::P4,
+			"P5" => Self::P5,
+			"P6" => Self::P6,
+			"P7" => Self::P7,
+			"P8" => Self::P8,
+			"P9" => Self::P9,
+			"P10" => Self::P10,
+			"P11" => Self::P11,
+			"P12" => Self::P12,
+			_ => return None,
+		};
+
+		Some(process)
+	}
+
+	pub fn code(&self) -> &'static str {
+		match self {
+			Self::P1 => "P1",
+			Self::P2 => "P2",
+			Self::P3 => "P3",
+			Self::P4 => "P4",
+			Self::P5 => "P5",
+			Self::P6 => "P6",
+			Self::P7 => "P7",
+			Self::P8 => "P8",
+			Self::P9 => "P9",
+			Self::P10 => "P10",
+			Self::P11 => "P11",
+			Self::P12 => "P12",
+		}
+	}
+
+	pub fn description_hr(&self) -> &'static str {
+		match self {
+			Self::P1 => "Fakturiranje isporuka dobara i usluga preko narudžbi na temelju ugovora",
+			Self::P2 => "Periodično fakturiranje isporuka na temelju ugovora",
+			Self::P3 => "Fakturiranje isporuka preko nepredviđene narudžbe",
+			Self::P4 => "Plaćanje predujma (avansno plaćanje)",
+			Self::P5 => "Plaćanje na licu mjesta",
+			Self::P6 => "Plaćanje prije isporuke na temelju narudžbe",
+			Self::P7 => "Računi s referencama na otpremnicu",
+			Self::P8 => "Računi s referencama na otpremnicu i primku",
+			Self::P9 => "Odobrenje ili negativno fakturiranje",
+			Self::P10 => "Korektivno fakturiranje",
+			Self::P11 => "Parcijalno i konačno fakturiranje",
+			Self::P12 => "Samoizdavanje računa",
+		}
+	}
+
+	pub fn description_en(&self) -> &'static str {
+		match self {
+			Self::P1 => "Invoicing of deliveries of goods and services against purchase orders, based on a contract",
+			Self::P2 => "Periodic invoicing of contract-based deliveries",
+			Self::P3 => "Invoicing of deliveries against an unforeseen order",
+			Self::P4 => "Advance payment",
+			Self::P5 => "Spot payment",
+			Self::P6 => "Payment before delivery based on a purchase order",
+			Self::P7 => "Invoices with references to a despatch advice",
+			Self::P8 => "Invoices with references to a despatch advice and a receipt advice",
+			Self::P9 => "Credit notes or invoices with negative amounts",
+			Self::P10 => "Corrective invoicing",
+			Self::P11 => "Partial and final invoicing",
+			Self::P12 => "Self-billing",
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn hr_cius(version: Option<&str>) -> Specification {
+		Specification::HrCius { version: version.map(str::to_owned) }
+	}
+
+	#[test]
+	fn customization_id() {
+		let cases = [
+			("urn:cen.eu:en16931:2017#compliant#urn:mingo.hr:2018:1.0", hr_cius(Some("1.0"))),
+			("urn:cen.eu:en16931:2017#compliant#urn:mingo.hr:2018:2.1.3", hr_cius(Some("2.1.3"))),
+			(" urn:cen.eu:en16931:2017#compliant#urn:mingo.hr:2018:1.0\n", hr_cius(Some("1.0"))),
+			("urn:cen.eu:en16931:2017#compliant#urn:mingo.hr:2018", hr_cius(None)),
+			("urn:cen.eu:en16931:2017", Specification::En16931),
+			(
+				"urn:cen.eu:en16931:2017#compliant#urn:fdc:peppol.eu:2017:poacc:billing:3.0",
+				Specification::PeppolBis3,
+			),
+		];
+		for (id, expected) in cases {
+			assert_eq!(Specification::parse(id), expected, "{:?}", id);
+		}
+	}
+
+	// Nepoznati i strani identifikatori, te slični identifikatoru HR CIUS koji to nisu
+	#[test]
+	fn unknown_customization_id() {
+		let cases = [
+			"",
+			"urn:cen.eu:en16931:2017#compliant#urn:mingo.hr:2018:",
+			"urn:cen.eu:en16931:2017#compliant#urn:mingo.hr:20181.0",
+			"urn:cen.eu:en16931:2017#compliant#urn:xeinkauf.de:kosit:xrechnung_3.0",
+			"urn:cen.eu:en16931:2017#conformant#urn:fdc:peppol.eu:2017:poacc:billing:international:aunz:3.0",
+			"urn:cen.eu:en16931:2017#compliant#urn:fdc:peppol.eu:2017:poacc:billing:3.0:extra",
+			"urn:cen.eu:en16931:2018",
+		];
+		for id in cases {
+			assert_eq!(Specification::parse(id), Specification::Unknown(id.to_owned()), "{:?}", id);
+		}
+	}
+
+	#[test]
+	fn business_process() {
+		let all = [
+			BusinessProcess::P1,
+			BusinessProcess::P2,
+			BusinessProcess::P3,
+			BusinessProcess::P4,
+			BusinessProcess::P5,
+			BusinessProcess::P6,
+			BusinessProcess::P7,
+			BusinessProcess::P8,
+			BusinessProcess::P9,
+			BusinessProcess::P10,
+			BusinessProcess::P11,
+			BusinessProcess::P12,
+		];
+		for (i, process) in all.into_iter().enumerate() {
+			let code = std::format!("P{}", i + 1);
+			assert_eq!(process.code(), code);
+			assert_eq!(BusinessProcess::parse(&code), Some(process));
+			assert!(!process.description_hr().is_empty() && !process.description_en().is_empty());
+		}
+		assert_eq!(BusinessProcess::parse(" P10 "), Some(BusinessProcess::P10));
+
+		for profile in [
+			"",
+			"P0",
+			"P13",
+			"p1",
+			"P01",
+			"P 1",
+			"1",
+			"urn:fdc:peppol.eu:2017:poacc:billing:01:1.0",
+		] {
+			assert_eq!(BusinessProcess::parse(profile), None, "{:?}", profile);
+		}
+	}
+}
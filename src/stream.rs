@@ -0,0 +1,201 @@
+// Čitanje računa stavku po stavku
+//
+// Zaglavlje (XmlData bez stavki) dostupno je odmah, a stavke se vraćaju redom kako se u dokumentu zatvaraju,
+// pa je u memoriji uvijek samo jedna stavka. Zaglavlje je potpuno jer UBL sve ostale elemente navodi prije stavki.
+// Uz ParseOptions::lossless nepoznati elementi stavke ne ostaju u zaglavlju, nego se vraćaju uz stavku (unknown),
+// a ograničenje Limits::max_invoice_lines se ne primjenjuje.
+
+use crate::{
+	ParseError, ParseOptions,
+	invoice::{InvoiceLine, UnknownElement, XmlData},
+	limits::Limits,
+	mapping::Missing,
+	parser::{self, Progress},
+	path::Path,
+	reader::Reader,
+};
+use std::io::Read;
+
+const LINE_PATH: &str = "/Invoice/cac:InvoiceLine";
+
+pub struct InvoiceStream<R: Read> {
+	parser: Reader<R>,
+	path: Path,
+	unknown: Vec<Option<usize>>,
+	options: ParseOptions,
+	xml_data: XmlData,
+	// Prva stavka se pročita već pri otvaranju, zajedno sa zaglavljem
+	pending: Option<(InvoiceLine, Vec<UnknownElement>)>,
+	// Nepoznati elementi posljednje vraćene stavke
+	line_unknown: Vec<UnknownElement>,
+	lines: usize,
+	done: bool,
+}
+
+impl<R: Read> InvoiceStream<R> {
+	pub(crate) fn new(reader: R, options: &ParseOptions) -> Result<Self, ParseError> {
+		// Stavke se ne drže u memoriji, pa njihov broj nije potrebno ograničiti
+		let limits = Limits { max_invoice_lines: usize::MAX, ..options.limits.clone() };
+		let mut stream = Self {
+			parser: Reader::new(reader, &limits),
+			path: Path::default(),
+			unknown: Vec::new(),
+			options: options.clone(),
+			xml_data: XmlData::default(),
+			pending: None,
+			line_unknown: Vec::new(),
+			lines: 0,
+			done: false,
+		};
+		parser::read_root(&mut stream.parser, &mut stream.path)?;
+		stream.pending = stream.read_line()?;
+
+		Ok(stream)
+	}
+
+	// Podaci računa bez stavki, invoice_lines je uvijek prazan
+	pub fn header(&self) -> &XmlData {
+		&self.xml_data
+	}
+
+	// Nepoznati elementi i atributi posljednje vraćene stavke, samo uz ParseOptions::lossless
+	pub fn unknown(&self) -> &[UnknownElement] {
+		&self.line_unknown
+	}
+
+	// Broj do sada vraćenih stavki
+	pub fn lines(&self) -> usize {
+		self.lines
+	}
+
+	// Obavezna polja zaglavlja koja nedostaju, stavke se provjeravaju s InvoiceLine::missing_fields
+	pub fn missing_fields(&self) -> Vec<Missing> {
+		let has_lines = self.lines > 0 || self.pending.is_some();
+		self.xml_data
+			.missing_fields()
+			.into_iter()
+			.filter(|x| !(has_lines && x.field.id == "BG-25"))
+			.collect()
+	}
+
+	// Preskače preostale stavke i vraća zaglavlje
+	pub fn finish(mut self) -> Result<XmlData, ParseError> {
+		for line in &mut self {
+			line?;
+		}
+
+		Ok(self.xml_data)
+	}
+
+	fn read_line(&mut self) -> Result<Option<(InvoiceLine, Vec<UnknownElement>)>, ParseError> {
+		if self.done {
+			return Ok(None);
+		}
+
+		let progress = parser::main_logic(
+			&mut self.parser, &mut self.xml_data, &mut self.path, &mut self.unknown, &self.options, true,
+		)?;
+		match progress {
+			Progress::InvoiceLine => {
+				// Stavka je zatvorena, pa njeni nepoznati elementi više nisu na stogu otvorenih elemenata
+				let unknown = &mut self.xml_data.unknown;
+				let start = unknown
+					.iter()
+					.position(|x| is_line_path(&x.path))
+					.unwrap_or(unknown.len());
+				let line_unknown = unknown.split_off(start);
+				Ok(self.xml_data.invoice_lines.pop().map(|line| (line, line_unknown)))
+			},
+			Progress::EndDocument => {
+				self.done = true;
+				Ok(None)
+			},
+		}
+	}
+}
+
+impl<R: Read> Iterator for InvoiceStream<R> {
+	type Item = Result<InvoiceLine, ParseError>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let line = match self.pending.take() {
+			Some(line) => Ok(Some(line)),
+			None => self.read_line(),
+		};
+		match line {
+			Ok(Some((line, unknown))) => {
+				self.lines += 1;
+				self.line_unknown = unknown;
+				Some(Ok(line))
+			},
+			Ok(None) => None,
+			Err(e) => {
+				// Nakon greške dokument se više ne čita
+				self.done = true;
+				Some(Err(e))
+			},
+		}
+	}
+}
+
+// Putanja stavke ili nekog njenog podelementa
+fn is_line_path(path: &str) -> bool {
+	path.strip_prefix(LINE_PATH)
+		.is_some_and(|x| x.is_empty() || x.starts_with('/'))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	// Primjer iz tests/data s tri jednake stavke
+	fn invoice() -> String {
+		let xml = include_str!("../tests/data/invoice.xml");
+		let start = xml.find("\t<cac:InvoiceLine>").unwrap();
+		let end = xml.find("</cac:InvoiceLine>").unwrap() + "</cac:InvoiceLine>\n".len();
+		std::format!("{}{}{}", &xml[..end], xml[start..end].repeat(2), &xml[end..])
+	}
+
+	#[test]
+	fn line_limit_not_applied() {
+		let options = ParseOptions {
+			limits: Limits { max_invoice_lines: 1, ..Limits::default() },
+			..Default::default()
+		};
+		let xml = invoice();
+		assert!(crate::parse_invoice_with(xml.as_bytes(), &options).is_err());
+
+		let stream = InvoiceStream::new(xml.as_bytes(), &options).unwrap();
+		let lines: Vec<InvoiceLine> = stream.collect::<Result<_, _>>().unwrap();
+		assert_eq!(lines.len(), 3);
+	}
+
+	#[test]
+	fn unknown_elements_per_line() {
+		let options = ParseOptions { lossless: true, ..Default::default() };
+		let xml = invoice().replace(
+			"<cbc:ID>1</cbc:ID>\n\t\t<cbc:Invoiced",
+			"<cbc:ID>1</cbc:ID><x:Extra xmlns:x=\"urn:x\">a</x:Extra>\n\t\t<cbc:Invoiced",
+		);
+		let mut stream = InvoiceStream::new(xml.as_bytes(), &options).unwrap();
+		let header_unknown = stream.header().unknown.len();
+		assert!(stream.header().unknown.iter().all(|x| !is_line_path(&x.path)));
+
+		let mut extra = Vec::new();
+		while let Some(line) = stream.next() {
+			line.unwrap();
+			assert!(stream.unknown().iter().all(|x| is_line_path(&x.path)));
+			extra.push(stream.unknown().iter().filter(|x| x.path.ends_with("Extra")).count());
+		}
+		assert_eq!(extra, [1, 1, 1]);
+		assert_eq!(stream.header().unknown.len(), header_unknown);
+	}
+
+	#[test]
+	fn line_path() {
+		assert!(is_line_path("/Invoice/cac:InvoiceLine"));
+		assert!(is_line_path("/Invoice/cac:InvoiceLine/cbc:ID"));
+		assert!(!is_line_path("/Invoice/cac:InvoiceLineX"));
+		assert!(!is_line_path("/Invoice/cbc:ID"));
+	}
+}
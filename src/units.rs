@@ -0,0 +1,158 @@
+// Nazivi jedinica mjere za prikaz
+//
+// Tablica units.tsv (šifra, kratki i dugi hrvatski naziv, kratki i dugi engleski naziv) pokriva jedinice koje se
+// u računima uobičajeno koriste, npr. H87 -> kom / komad. Za ostale šifre dugi naziv se uzima iz popisa UN/ECE
+// preporuke 20, a kratki je sama šifra. Tvrtka može nazive zamijeniti vlastitom datotekom istog oblika, u kojoj
+// prazni ili izostavljeni stupci zadržavaju ugrađeni naziv.
+
+use crate::{
+	codelist::CodeList,
+	decimal::Quantity,
+	language::Language,
+	money::{AmountFormat, CurrencyDisplay},
+};
+use std::{collections::HashMap, path::Path, sync::LazyLock};
+
+// Nazivi redom kao u stupcima datoteke: kratki i dugi hrvatski, kratki i dugi engleski
+type Names = [Option<String>; 4];
+
+static UNITS_TABLE: LazyLock<HashMap<String, Names>> = LazyLock::new(|| parse(include_str!("units.tsv")));
+
+fn parse(text: &str) -> HashMap<String, Names> {
+	text.lines()
+		.filter(|line| !line.starts_with('#') && !line.trim().is_empty())
+		.map(|line| {
+			let mut columns = line.split('\t').map(str::trim);
+			let code = columns.next().unwrap_or_default().to_owned();
+			let names = std::array::from_fn(|_| columns.next().filter(|x| !x.is_empty()).map(str::to_owned));
+			(code, names)
+		})
+		.collect()
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct UnitNames {
+	overrides: HashMap<String, Names>,
+}
+
+impl UnitNames {
+	// Nazivi tvrtke iz datoteke oblika units.tsv
+	pub fn load(path: &Path) -> std::io::Result<Self> {
+		Ok(Self::parse(&std::fs::read_to_string(path)?))
+	}
+
+	pub fn parse(text: &str) -> Self {
+		Self { overrides: parse(text) }
+	}
+
+	// Kratki naziv, npr. kom ili kg; šifra ako naziv nije poznat
+	pub fn short(&self, code: &str, language: Language) -> String {
+		let column = match language {
+			Language::Hr => 0,
+			Language::En => 2,
+		};
+		self.name(code, column).unwrap_or_else(|| code.trim().to_owned())
+	}
+
+	// Dugi naziv, npr. komad ili kilogram; naziv iz UN/ECE preporuke 20 ili šifra ako naziv nije poznat
+	pub fn long(&self, code: &str, language: Language) -> String {
+		let column = match language {
+			Language::Hr => 1,
+			Language::En => 3,
+		};
+		self.name(code, column)
+			.or_else(|| {
+				let unit = CodeList::Unit.get(code)?;
+				Some(
+					match language {
+						Language::Hr => unit.name_hr,
+						Language::En => unit.name_en,
+					}
+					.to_owned(),
+				)
+			})
+			.unwrap_or_else(|| code.trim().to_owned())
+	}
+
+	// Količina sa znakovima za decimale i tisućice jezika i kratkim nazivom jedinice, npr. 2,5 kg
+	pub fn quantity(&self, quantity: &Quantity, language: Language) -> String {
+		let number = AmountFormat::new(language, CurrencyDisplay::Code).number(quantity.value);
+		match &quantity.unit {
+			Some(unit) => std::format!("{} {}", number, self.short(unit, language)),
+			None => number,
+		}
+	}
+
+	fn name(&self, code: &str, column: usize) -> Option<String> {
+		let code = code.trim();
+		[self.overrides.get(code), UNITS_TABLE.get(code)]
+			.into_iter()
+			.flatten()
+			.find_map(|names| names[column].clone())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn quantity(value: &str, unit: Option<&str>) -> Quantity {
+		Quantity::new(value.parse().unwrap(), unit.map(str::to_owned))
+	}
+
+	#[test]
+	fn languages() {
+		let names = UnitNames::default();
+		let cases = [
+			("2.5", Some("KGM"), Language::Hr, "2,5 kg"),
+			("2.5", Some("KGM"), Language::En, "2.5 kg"),
+			("1234.50", Some("H87"), Language::Hr, "1.234,50 kom"),
+			("1234.50", Some("H87"), Language::En, "1,234.50 pcs"),
+			("-3", Some("HUR"), Language::En, "-3 h"),
+			("7", Some("XYZ"), Language::Hr, "7 XYZ"),
+			("0.125", None, Language::Hr, "0,125"),
+		];
+		for (value, unit, language, expected) in cases {
+			assert_eq!(names.quantity(&quantity(value, unit), language), expected);
+		}
+
+		assert_eq!(names.long("KGM", Language::Hr), "kilogram");
+		assert_eq!(names.long("HUR", Language::En), "hour");
+		// Jedinica koje nema u units.tsv dobiva naziv iz UN/ECE preporuke 20
+		assert_eq!(names.short("GRO", Language::Hr), "GRO");
+		assert_eq!(names.long("GRO", Language::Hr), "gros");
+		assert_eq!(names.long("XYZ", Language::En), "XYZ");
+	}
+
+	// C62 je jedinica (one), a komad je H87
+	#[test]
+	fn agrees_with_code_list() {
+		let names = UnitNames::default();
+		for code in ["C62", "H87"] {
+			let unit = CodeList::Unit.get(code).unwrap();
+			assert_eq!(names.long(code, Language::Hr), unit.name_hr);
+			assert_eq!(names.long(code, Language::En), unit.name_en);
+		}
+	}
+
+	#[test]
+	fn overrides() {
+		let names = UnitNames::parse(
+			"# šifra\tkratki hr\tdugi hr\tkratki en\tdugi en\nH87\tkomad\t\nC62\t\t\tea\teach\nXYZ\tx\tiks\n",
+		);
+		// Prazni i izostavljeni stupci zadržavaju ugrađeni naziv
+		assert_eq!(names.short("H87", Language::Hr), "komad");
+		assert_eq!(names.long("H87", Language::Hr), "komad");
+		assert_eq!(names.short("H87", Language::En), "pcs");
+		assert_eq!(names.short("C62", Language::Hr), "jed.");
+		assert_eq!(names.short("C62", Language::En), "ea");
+		assert_eq!(names.long("C62", Language::En), "each");
+		assert_eq!(names.long("C62", Language::Hr), "jedinica");
+		// Šifra koje nema u ugrađenoj tablici
+		assert_eq!(names.quantity(&quantity("1.5", Some("XYZ")), Language::Hr), "1,5 x");
+		assert_eq!(names.long("XYZ", Language::Hr), "iks");
+		assert_eq!(names.short("XYZ", Language::En), "XYZ");
+		// Ostale jedinice su ugrađene
+		assert_eq!(names.short("KGM", Language::En), "kg");
+	}
+}
@@ -0,0 +1,324 @@
+// Iznos slovima, npr. "tisuću dvjesto trideset četiri eura i pedeset šest centi"
+//
+// Iznos se najprije zaokružuje na decimale valute. Na hrvatskom se brojevi slažu s rodom imenice (jedan euro,
+// jedna kuna, dvije tisuće), a imenica s brojem (jedan cent, dva centa, pet centi, jedanaest centi). Za valute
+// koje nisu u tablici CURRENCIES ispisuje se šifra valute, a decimale kao razlomak, npr. "... CHF i 56/100".
+// Podržani su iznosi do 999 milijardi.
+
+use crate::{
+	decimal::Amount,
+	language::Language,
+	money::{self, AmountFormat},
+};
+
+struct Nouns {
+	code: &'static str,
+	// Rod i oblici uz 1, 2 do 4 i 5 ili više, za jedinicu i za stoti dio
+	hr: (Gender, [&'static str; 3]),
+	hr_minor: (Gender, [&'static str; 3]),
+	// Jednina i množina
+	en: [&'static str; 2],
+	en_minor: [&'static str; 2],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Gender {
+	Masculine,
+	Feminine,
+}
+
+const CURRENCIES: &[Nouns] = &[
+	Nouns {
+		code: "EUR",
+		hr: (Gender::Masculine, ["euro", "eura", "eura"]),
+		hr_minor: (Gender::Masculine, ["cent", "centa", "centi"]),
+		en: ["euro", "euros"],
+		en_minor: ["cent", "cents"],
+	},
+	Nouns {
+		code: "HRK",
+		hr: (Gender::Feminine, ["kuna", "kune", "kuna"]),
+		hr_minor: (Gender::Feminine, ["lipa", "lipe", "lipa"]),
+		en: ["kuna", "kuna"],
+		en_minor: ["lipa", "lipa"],
+	},
+	Nouns {
+		code: "USD",
+		hr: (Gender::Masculine, ["dolar", "dolara", "dolara"]),
+		hr_minor: (Gender::Masculine, ["cent", "centa", "centi"]),
+		en: ["dollar", "dollars"],
+		en_minor: ["cent", "cents"],
+	},
+	Nouns {
+		code: "GBP",
+		hr: (Gender::Feminine, ["funta", "funte", "funti"]),
+		hr_minor: (Gender::Masculine, ["peni", "penija", "penija"]),
+		en: ["pound", "pounds"],
+		en_minor: ["penny", "pence"],
+	},
+];
+
+const MAX: u64 = 999_999_999_999;
+
+// Iznos slovima, None ako je iznos veći od 999 milijardi
+pub fn amount_in_words(amount: &Amount, document_currency: &str, language: Language) -> Option<String> {
+	let currency = amount.currency.as_deref().unwrap_or(document_currency).trim();
+	let minor_units = money::minor_units(currency);
+	let value = amount.value.round(minor_units);
+	let (int, frac) = value.digits();
+	let major: u64 = int.parse().ok().filter(|x| *x <= MAX)?;
+	let minor: u64 = match frac.is_empty() {
+		true => 0,
+		false => frac.parse().ok()?,
+	};
+	let nouns = CURRENCIES
+		.iter()
+		.find(|x| x.code == currency)
+		.filter(|_| minor_units == 2);
+
+	let mut words = Vec::new();
+	if value.is_negative() && !value.is_zero() {
+		words.push("minus".to_owned());
+	}
+	match (language, nouns) {
+		(Language::Hr, Some(nouns)) => {
+			words.push(hr_counted(major, nouns.hr));
+			if minor > 0 {
+				words.push("i".to_owned());
+				words.push(hr_counted(minor, nouns.hr_minor));
+			}
+		},
+		(Language::En, Some(nouns)) => {
+			words.push(en_counted(major, nouns.en));
+			if minor > 0 {
+				words.push("and".to_owned());
+				words.push(en_counted(minor, nouns.en_minor));
+			}
+		},
+		(_, None) => {
+			let number = match language {
+				Language::Hr => hr_number(major, Gender::Masculine),
+				Language::En => en_number(major),
+			};
+			words.push(std::format!("{} {}", number, currency));
+			if minor > 0 {
+				words.push(if language == Language::Hr { "i" } else { "and" }.to_owned());
+				words.push(std::format!("{}/1{}", frac, "0".repeat(frac.len())));
+			}
+		},
+	}
+
+	Some(words.join(" "))
+}
+
+// Broj slovima, u muškom rodu na hrvatskom
+pub fn number_in_words(n: u64, language: Language) -> Option<String> {
+	if n > MAX {
+		return None;
+	}
+	Some(match language {
+		Language::Hr => hr_number(n, Gender::Masculine),
+		Language::En => en_number(n),
+	})
+}
+
+// Za ispis uz iznos, npr. "1.234,56 € (slovima: tisuću dvjesto trideset četiri eura i pedeset šest centi)"
+pub fn amount_with_words(amount: &Amount, document_currency: &str, format: &AmountFormat) -> String {
+	let formatted = format.format(amount, document_currency);
+	let label = match format.language {
+		Language::Hr => "slovima",
+		Language::En => "in words",
+	};
+	match amount_in_words(amount, document_currency, format.language) {
+		Some(words) => std::format!("{} ({}: {})", formatted, label, words),
+		None => formatted,
+	}
+}
+
+const HR_ONES: [&str; 10] = [
+	"nula", "jedan", "dva", "tri", "četiri", "pet", "šest", "sedam", "osam", "devet",
+];
+const HR_TEENS: [&str; 10] = [
+	"deset", "jedanaest", "dvanaest", "trinaest", "četrnaest", "petnaest", "šesnaest", "sedamnaest", "osamnaest",
+	"devetnaest",
+];
+const HR_TENS: [&str; 10] = [
+	"", "", "dvadeset", "trideset", "četrdeset", "pedeset", "šezdeset", "sedamdeset", "osamdeset", "devedeset",
+];
+const HR_HUNDREDS: [&str; 10] = [
+	"", "sto", "dvjesto", "tristo", "četiristo", "petsto", "šesto", "sedamsto", "osamsto", "devetsto",
+];
+
+// Oblik imenice uz broj: 1, 21 -> prvi; 2 do 4, 22 do 24 -> drugi; 5 do 20 i ostalo -> treći
+fn hr_form(n: u64, forms: [&'static str; 3]) -> &'static str {
+	match (n % 10, n % 100) {
+		(_, 11..=14) => forms[2],
+		(1, _) => forms[0],
+		(2..=4, _) => forms[1],
+		_ => forms[2],
+	}
+}
+
+fn hr_counted(n: u64, (gender, forms): (Gender, [&'static str; 3])) -> String {
+	std::format!("{} {}", hr_number(n, gender), hr_form(n, forms))
+}
+
+fn hr_below_thousand(n: u64, gender: Gender, out: &mut Vec<&'static str>) {
+	let (hundreds, rest) = (n / 100, n % 100);
+	if hundreds > 0 {
+		out.push(HR_HUNDREDS[hundreds as usize]);
+	}
+	if (10..20).contains(&rest) {
+		out.push(HR_TEENS[rest as usize - 10]);
+		return;
+	}
+	if rest >= 20 {
+		out.push(HR_TENS[rest as usize / 10]);
+	}
+	out.push(match (rest % 10, gender) {
+		(0, _) => return,
+		(1, Gender::Feminine) => "jedna",
+		(2, Gender::Feminine) => "dvije",
+		(ones, _) => HR_ONES[ones as usize],
+	});
+}
+
+fn hr_number(n: u64, gender: Gender) -> String {
+	if n == 0 {
+		return HR_ONES[0].to_owned();
+	}
+
+	// Uz 1 stoji samo imenica, u obliku "tisuću" kao u "tisuću eura"
+	let scales = [
+		(1_000_000_000, Gender::Feminine, "milijardu", ["milijarda", "milijarde", "milijardi"]),
+		(1_000_000, Gender::Masculine, "milijun", ["milijun", "milijuna", "milijuna"]),
+		(1_000, Gender::Feminine, "tisuću", ["tisuća", "tisuće", "tisuća"]),
+	];
+	let mut out = Vec::new();
+	for (scale, scale_gender, one, forms) in scales {
+		let count = n / scale % 1000;
+		match count {
+			0 => (),
+			1 => out.push(one),
+			_ => {
+				hr_below_thousand(count, scale_gender, &mut out);
+				out.push(hr_form(count, forms));
+			},
+		}
+	}
+	hr_below_thousand(n % 1000, gender, &mut out);
+
+	out.join(" ")
+}
+
+const EN_ONES: [&str; 20] = [
+	"zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten", "eleven", "twelve",
+	"thirteen", "fourteen", "fifteen", "sixteen", "seventeen", "eighteen", "nineteen",
+];
+const EN_TENS: [&str; 10] = [
+	"", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+
+fn en_counted(n: u64, forms: [&'static str; 2]) -> String {
+	std::format!("{} {}", en_number(n), if n == 1 { forms[0] } else { forms[1] })
+}
+
+fn en_below_thousand(n: u64, out: &mut Vec<String>) {
+	let (hundreds, rest) = (n / 100, n % 100);
+	if hundreds > 0 {
+		out.push(std::format!("{} hundred", EN_ONES[hundreds as usize]));
+	}
+	match rest {
+		0 => (),
+		1..20 => out.push(EN_ONES[rest as usize].to_owned()),
+		_ if rest % 10 == 0 => out.push(EN_TENS[rest as usize / 10].to_owned()),
+		_ => out.push(std::format!("{}-{}", EN_TENS[rest as usize / 10], EN_ONES[rest as usize % 10])),
+	}
+}
+
+fn en_number(n: u64) -> String {
+	if n == 0 {
+		return EN_ONES[0].to_owned();
+	}
+
+	let mut out = Vec::new();
+	for (scale, name) in [(1_000_000_000, "billion"), (1_000_000, "million"), (1_000, "thousand")] {
+		let count = n / scale % 1000;
+		if count > 0 {
+			en_below_thousand(count, &mut out);
+			out.push(name.to_owned());
+		}
+	}
+	en_below_thousand(n % 1000, &mut out);
+
+	out.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::money::CurrencyDisplay;
+
+	fn words(value: &str, currency: &str, language: Language) -> Option<String> {
+		amount_in_words(&Amount::new(value.parse().unwrap(), None), currency, language)
+	}
+
+	#[test]
+	fn croatian() {
+		let cases = [
+			("1234.56", "EUR", "tisuću dvjesto trideset četiri eura i pedeset šest centi"),
+			("1", "EUR", "jedan euro"),
+			("21.01", "EUR", "dvadeset jedan euro i jedan cent"),
+			("11.12", "EUR", "jedanaest eura i dvanaest centi"),
+			("2", "HRK", "dvije kune"),
+			("22000.02", "HRK", "dvadeset dvije tisuće kuna i dvije lipe"),
+			("2000000", "EUR", "dva milijuna eura"),
+			("1000000000", "EUR", "milijardu eura"),
+			("1.01", "GBP", "jedna funta i jedan peni"),
+			("-5", "EUR", "minus pet eura"),
+			("0.004", "EUR", "nula eura"),
+		];
+		for (value, currency, expected) in cases {
+			assert_eq!(words(value, currency, Language::Hr).as_deref(), Some(expected), "{}", value);
+		}
+	}
+
+	#[test]
+	fn english() {
+		let cases = [
+			("1234.56", "EUR", "one thousand two hundred thirty-four euros and fifty-six cents"),
+			("1", "USD", "one dollar"),
+			("0.01", "GBP", "zero pounds and one penny"),
+			("40.5", "EUR", "forty euros and fifty cents"),
+		];
+		for (value, currency, expected) in cases {
+			assert_eq!(words(value, currency, Language::En).as_deref(), Some(expected), "{}", value);
+		}
+	}
+
+	#[test]
+	fn other_currencies() {
+		assert_eq!(words("123.45", "CHF", Language::Hr).as_deref(), Some("sto dvadeset tri CHF i 45/100"));
+		assert_eq!(words("123.45", "CHF", Language::En).as_deref(), Some("one hundred twenty-three CHF and 45/100"));
+		// JPY nema decimala, pa se iznos zaokružuje na cijeli broj
+		assert_eq!(words("1500.7", "JPY", Language::Hr).as_deref(), Some("tisuću petsto jedan JPY"));
+	}
+
+	#[test]
+	fn limits() {
+		assert_eq!(words("999999999999", "EUR", Language::En).map(|x| x.starts_with("nine hundred")), Some(true));
+		assert_eq!(words("1000000000000", "EUR", Language::Hr), None);
+		assert_eq!(number_in_words(MAX + 1, Language::Hr), None);
+		assert_eq!(number_in_words(0, Language::En).as_deref(), Some("zero"));
+	}
+
+	#[test]
+	fn with_formatted_amount() {
+		let amount = Amount::new("1234.56".parse().unwrap(), None);
+		let format = AmountFormat::new(Language::Hr, CurrencyDisplay::Symbol);
+		assert_eq!(
+			amount_with_words(&amount, "EUR", &format),
+			"1.234,56 € (slovima: tisuću dvjesto trideset četiri eura i pedeset šest centi)"
+		);
+	}
+}
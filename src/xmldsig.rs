@@ -0,0 +1,445 @@
+// Provjera omotanog (enveloped) XAdES potpisa bez pristupa mreži
+//
+// Lanac certifikata provjerava se isključivo prema certifikatima iz lokalnog direktorija
+// (npr. FINA RDC korijenski certifikati), bez OCSP-a i CRL-a.
+//
+// Provjerava se samo potpis na mjestu koje propisuje UBL (sac:SignatureInformation/ds:Signature u ext:UBLExtensions),
+// isti onaj iz kojeg parser puni SignatureInfo. Referenca na xades:SignedProperties je obavezna, a njegov
+// xades:CertDigest mora odgovarati certifikatu potpisnika.
+//
+// Ograničenje: lanac se provjerava u trenutku xades:SigningTime, a to vrijeme navodi sam potpisnik. Bez vremenskog
+// žiga (XAdES-T) ne može se dokazati da je potpis nastao dok je certifikat vrijedio, pa se odbija samo vrijeme
+// potpisivanja u budućnosti. Opoziv certifikata se ne provjerava.
+
+use crate::{
+	c14n::{Document, Element, Method},
+	limits::Limits,
+	namespace::{EXT, INVOICE, SAC, SIG},
+	signature::SignatureInfo,
+};
+use base64::{Engine, engine::general_purpose::STANDARD};
+use ring::{digest, signature};
+use std::path::Path;
+use time::{OffsetDateTime, format_description::well_known::Rfc3339};
+use x509_parser::{
+	pem::Pem,
+	prelude::{FromDer, X509Certificate},
+};
+
+pub const DS: &str = "http://www.w3.org/2000/09/xmldsig#";
+pub const XADES: &str = "http://uri.etsi.org/01903/v1.3.2#";
+const ENVELOPED_SIGNATURE: &str = "http://www.w3.org/2000/09/xmldsig#enveloped-signature";
+const EC: &str = "http://www.w3.org/2001/10/xml-exc-c14n#";
+const SIGNED_PROPERTIES: &str = "http://uri.etsi.org/01903#SignedProperties";
+const MAX_CHAIN_LENGTH: usize = 10;
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(tag = "status", content = "reasons", rename_all = "kebab-case")]
+pub enum Verification {
+	Valid,
+	Invalid(Vec<String>),
+	UntrustedChain(Vec<String>),
+}
+
+#[derive(Debug, Default)]
+pub struct TrustStore {
+	certificates: Vec<Vec<u8>>,
+}
+
+impl TrustStore {
+	// Učitava sve PEM i DER certifikate iz direktorija
+	pub fn load(dir: &Path) -> std::io::Result<Self> {
+		let mut certificates = Vec::new();
+		for entry in std::fs::read_dir(dir)? {
+			let path = entry?.path();
+			if !path.is_file() {
+				continue;
+			}
+
+			let bytes = std::fs::read(&path)?;
+			if bytes.starts_with(b"-----BEGIN") {
+				for pem in Pem::iter_from_buffer(&bytes).flatten() {
+					certificates.push(pem.contents);
+				}
+			} else if X509Certificate::from_der(&bytes).is_ok() {
+				certificates.push(bytes);
+			}
+		}
+
+		Ok(Self { certificates })
+	}
+}
+
+pub fn verify(document: &[u8], trust_store: &TrustStore, signature_info: &SignatureInfo) -> Verification {
+	verify_with(document, trust_store, signature_info, &Limits::default())
+}
+
+// Provjera potpisa čiji su podaci u signature_info, za dokument koji je parsiran s istim ograničenjima
+pub fn verify_with(
+	document: &[u8],
+	trust_store: &TrustStore,
+	signature_info: &SignatureInfo,
+	limits: &Limits,
+) -> Verification {
+	let document = match Document::parse(document, limits) {
+		Ok(document) => document,
+		Err(e) => return Verification::Invalid(vec![std::format!("neispravan XML: {}", e)]),
+	};
+	let root = &document.root;
+	if let Some(id) = root.duplicate_id() {
+		return Verification::Invalid(vec![std::format!("ID '{}' se u dokumentu pojavljuje više puta", id)]);
+	}
+	let signatures = ubl_signatures(root);
+	let signature = match signatures.as_slice() {
+		[signature] => *signature,
+		[] => return Verification::Invalid(vec!["dokument nije potpisan".to_owned()]),
+		_ => return Verification::Invalid(vec!["dokument ima više potpisa".to_owned()]),
+	};
+	let Some(signed_info) = signature.child(DS, "SignedInfo") else {
+		return Verification::Invalid(vec!["nedostaje ds:SignedInfo".to_owned()]);
+	};
+
+	let certificates: Vec<Vec<u8>> = signature
+		.child(DS, "KeyInfo")
+		.and_then(|x| x.child(DS, "X509Data"))
+		.map(|x| x.elements().filter(|c| c.is(DS, "X509Certificate")).collect())
+		.unwrap_or_else(Vec::new)
+		.into_iter()
+		.filter_map(|x| decode_base64(&x.text()))
+		.collect();
+	let Some(signer) = certificates.first() else {
+		return Verification::Invalid(vec!["nedostaje certifikat potpisnika".to_owned()]);
+	};
+	let Ok((_, signer_cert)) = X509Certificate::from_der(signer) else {
+		return Verification::Invalid(vec!["neispravan certifikat potpisnika".to_owned()]);
+	};
+	if signature_info.certificate.as_ref() != Some(signer) {
+		return Verification::Invalid(vec!["certifikat potpisnika ne odgovara prikazanom potpisu".to_owned()]);
+	}
+
+	let mut reasons = Vec::new();
+	check_references(&document, signature, signed_info, &mut reasons);
+	check_signature_value(signature, signed_info, &signer_cert, &mut reasons);
+	let signed_properties = signed_properties(root, signature, signed_info, &mut reasons);
+	if let Some(signed_properties) = signed_properties {
+		check_signing_certificate(signed_properties, signer, &mut reasons);
+	}
+	let now = OffsetDateTime::now_utc();
+	let signing_time = signed_properties.and_then(signing_time);
+	if signing_time.is_some_and(|x| x > now) {
+		reasons.push("vrijeme potpisivanja je u budućnosti".to_owned());
+	}
+	if !reasons.is_empty() {
+		return Verification::Invalid(reasons);
+	}
+
+	let at = signing_time.unwrap_or(now).unix_timestamp();
+	check_chain(&signer_cert, &certificates[1..], trust_store, at, &mut reasons);
+	if !reasons.is_empty() {
+		return Verification::UntrustedChain(reasons);
+	}
+
+	Verification::Valid
+}
+
+// ds:Signature na putanji iz parser::SIGNATURE_PATH, u svim ext:UBLExtension elementima
+fn ubl_signatures(root: &Element) -> Vec<&Element> {
+	if !root.is(INVOICE, "Invoice") {
+		return Vec::new();
+	}
+
+	let path = [
+		(EXT, "UBLExtensions"),
+		(EXT, "UBLExtension"),
+		(EXT, "ExtensionContent"),
+		(SIG, "UBLDocumentSignatures"),
+		(SAC, "SignatureInformation"),
+		(DS, "Signature"),
+	];
+	path.iter().fold(vec![root], |elements, (namespace, local_name)| {
+		elements
+			.into_iter()
+			.flat_map(|x| x.elements().filter(|x| x.is(namespace, local_name)))
+			.collect()
+	})
+}
+
+fn check_references(document: &Document, signature: &Element, signed_info: &Element, reasons: &mut Vec<String>) {
+	let mut covers_document = false;
+	for reference in signed_info.elements().filter(|x| x.is(DS, "Reference")) {
+		let uri = reference.attribute("URI").unwrap_or("");
+		// None za cijeli dokument
+		let target = match uri.strip_prefix('#') {
+			None if uri.is_empty() => None,
+			Some(id) => match document.root.find_by_id(id) {
+				Some(target) => Some(target),
+				None => {
+					reasons.push(std::format!("referenca '{}' ne postoji u dokumentu", uri));
+					continue;
+				},
+			},
+			None => {
+				reasons.push(std::format!("vanjska referenca '{}' nije podržana", uri));
+				continue;
+			},
+		};
+
+		let mut enveloped = false;
+		let mut method = Method::Inclusive;
+		let transforms = reference.child(DS, "Transforms");
+		for transform in transforms.iter().flat_map(|x| x.elements()) {
+			let algorithm = transform.attribute("Algorithm").unwrap_or("");
+			if algorithm == ENVELOPED_SIGNATURE {
+				enveloped = true;
+			} else if let Some(m) = Method::from_algorithm(algorithm) {
+				method = with_inclusive_prefixes(m, transform);
+			} else {
+				reasons.push(std::format!("transformacija '{}' nije podržana", algorithm));
+			}
+		}
+		if uri.is_empty() {
+			covers_document = enveloped;
+		}
+
+		let algorithm = reference
+			.child(DS, "DigestMethod")
+			.and_then(|x| x.attribute("Algorithm"))
+			.unwrap_or("");
+		let Some(algorithm) = digest_algorithm(algorithm) else {
+			reasons.push(std::format!("algoritam sažetka '{}' nije podržan", algorithm));
+			continue;
+		};
+
+		let exclude = if enveloped { Some(signature) } else { None };
+		let canonical = match target {
+			Some(target) => target.canonicalize(&method, exclude),
+			None => document.canonicalize(&method, exclude),
+		};
+		let actual = digest::digest(algorithm, &canonical);
+		let expected = reference
+			.child(DS, "DigestValue")
+			.and_then(|x| decode_base64(&x.text()))
+			.unwrap_or_default();
+		if actual.as_ref() != expected.as_slice() {
+			reasons.push(std::format!("sažetak reference '{}' ne odgovara sadržaju", uri));
+		}
+	}
+
+	if !covers_document {
+		reasons.push("potpis ne obuhvaća cijeli dokument".to_owned());
+	}
+}
+
+fn check_signature_value(
+	signature: &Element,
+	signed_info: &Element,
+	signer: &X509Certificate,
+	reasons: &mut Vec<String>,
+) {
+	let c14n = signed_info.child(DS, "CanonicalizationMethod");
+	let c14n_algorithm = c14n.and_then(|x| x.attribute("Algorithm")).unwrap_or("");
+	let Some(method) = Method::from_algorithm(c14n_algorithm) else {
+		reasons.push(std::format!("kanonikalizacija '{}' nije podržana", c14n_algorithm));
+		return;
+	};
+	let method = with_inclusive_prefixes(method, c14n.unwrap());
+
+	let algorithm = signed_info
+		.child(DS, "SignatureMethod")
+		.and_then(|x| x.attribute("Algorithm"))
+		.unwrap_or("");
+	let Some(algorithm) = signature_algorithm(algorithm) else {
+		reasons.push(std::format!("algoritam potpisa '{}' nije podržan", algorithm));
+		return;
+	};
+
+	let value = signature
+		.child(DS, "SignatureValue")
+		.and_then(|x| decode_base64(&x.text()))
+		.unwrap_or_default();
+	let public_key = signature::UnparsedPublicKey::new(algorithm, signer.public_key().subject_public_key.as_ref());
+	if public_key
+		.verify(&signed_info.canonicalize(&method, None), &value)
+		.is_err()
+	{
+		reasons.push("vrijednost potpisa nije ispravna".to_owned());
+	}
+}
+
+// xades:SignedProperties iz ovog potpisa na koji pokazuje referenca tipa SignedProperties
+fn signed_properties<'a>(
+	root: &'a Element,
+	signature: &Element,
+	signed_info: &Element,
+	reasons: &mut Vec<String>,
+) -> Option<&'a Element> {
+	let target = signed_info
+		.elements()
+		.filter(|x| x.is(DS, "Reference") && x.attribute("Type") == Some(SIGNED_PROPERTIES))
+		.filter_map(|x| x.attribute("URI")?.strip_prefix('#'))
+		.filter_map(|id| root.find_by_id(id))
+		.find(|x| x.is(XADES, "SignedProperties") && signature.contains(x));
+	if target.is_none() {
+		reasons.push("potpis ne obuhvaća xades:SignedProperties".to_owned());
+	}
+
+	target
+}
+
+// XAdES SigningCertificate mora odgovarati certifikatu iz ds:KeyInfo
+fn check_signing_certificate(signed_properties: &Element, signer: &[u8], reasons: &mut Vec<String>) {
+	let cert_digests: Vec<&Element> = signed_properties
+		.child(XADES, "SignedSignatureProperties")
+		.into_iter()
+		.flat_map(|x| x.elements())
+		.filter(|x| x.is(XADES, "SigningCertificate") || x.is(XADES, "SigningCertificateV2"))
+		.flat_map(|x| x.elements().filter(|x| x.is(XADES, "Cert")))
+		.filter_map(|x| x.child(XADES, "CertDigest"))
+		.collect();
+	if cert_digests.is_empty() {
+		reasons.push("nedostaje xades:CertDigest certifikata potpisnika".to_owned());
+		return;
+	}
+
+	let matches = cert_digests.iter().any(|cert_digest| {
+		let algorithm = cert_digest
+			.child(DS, "DigestMethod")
+			.and_then(|x| x.attribute("Algorithm"))
+			.unwrap_or("");
+		let expected = cert_digest
+			.child(DS, "DigestValue")
+			.and_then(|x| decode_base64(&x.text()))
+			.unwrap_or_default();
+		digest_algorithm(algorithm).is_some_and(|x| digest::digest(x, signer).as_ref() == expected.as_slice())
+	});
+	if !matches {
+		reasons.push("xades:SigningCertificate ne odgovara certifikatu potpisnika".to_owned());
+	}
+}
+
+fn check_chain(
+	signer: &X509Certificate,
+	intermediates: &[Vec<u8>],
+	trust_store: &TrustStore,
+	at: i64,
+	reasons: &mut Vec<String>,
+) {
+	let anchors: Vec<X509Certificate> = parse_all(&trust_store.certificates);
+	let intermediates: Vec<X509Certificate> = parse_all(intermediates);
+	let is_issuer = |issuer: &X509Certificate, cert: &X509Certificate| {
+		issuer.subject() == cert.issuer() && cert.verify_signature(Some(issuer.public_key())).is_ok()
+	};
+
+	if let Ok(Some(key_usage)) = signer.key_usage()
+		&& !key_usage.value.digital_signature()
+		&& !key_usage.value.non_repudiation()
+	{
+		reasons.push(std::format!("certifikat '{}' nije namijenjen potpisivanju", signer.subject()));
+		return;
+	}
+
+	let mut current = signer;
+	for _ in 0..MAX_CHAIN_LENGTH {
+		if !is_valid_at(current, at) {
+			reasons.push(std::format!("certifikat '{}' nije bio valjan u trenutku potpisivanja", current.subject()));
+			return;
+		}
+
+		if anchors.iter().any(|x| x.as_raw() == current.as_raw()) {
+			return;
+		}
+		if let Some(anchor) = anchors.iter().find(|x| is_issuer(x, current)) {
+			if !is_valid_at(anchor, at) {
+				reasons.push(std::format!("certifikat '{}' nije bio valjan u trenutku potpisivanja", anchor.subject()));
+			} else if !can_issue(anchor) {
+				reasons.push(std::format!("certifikat '{}' ne smije izdavati certifikate", anchor.subject()));
+			}
+			return;
+		}
+
+		match intermediates.iter().find(|x| is_issuer(x, current)) {
+			Some(issuer) if !can_issue(issuer) => {
+				reasons.push(std::format!("certifikat '{}' ne smije izdavati certifikate", issuer.subject()));
+				return;
+			},
+			Some(issuer) => current = issuer,
+			None => {
+				reasons.push(std::format!("nije pronađen pouzdani izdavatelj za '{}'", current.issuer()));
+				return;
+			},
+		}
+	}
+
+	reasons.push("lanac certifikata je predugačak".to_owned());
+}
+
+fn is_valid_at(certificate: &X509Certificate, at: i64) -> bool {
+	let validity = certificate.validity();
+	validity.not_before.timestamp() <= at && at <= validity.not_after.timestamp()
+}
+
+// Izdavatelj mora biti CA (basicConstraints), a ako navodi keyUsage, mora sadržavati keyCertSign
+fn can_issue(certificate: &X509Certificate) -> bool {
+	let key_cert_sign = match certificate.key_usage() {
+		Ok(Some(key_usage)) => key_usage.value.key_cert_sign(),
+		Ok(None) => true,
+		Err(_) => false,
+	};
+	certificate.is_ca() && key_cert_sign
+}
+
+fn parse_all(certificates: &[Vec<u8>]) -> Vec<X509Certificate<'_>> {
+	certificates
+		.iter()
+		.filter_map(|x| X509Certificate::from_der(x).ok().map(|(_, c)| c))
+		.collect()
+}
+
+fn signing_time(signed_properties: &Element) -> Option<OffsetDateTime> {
+	let time = signed_properties
+		.child(XADES, "SignedSignatureProperties")?
+		.child(XADES, "SigningTime")?
+		.text();
+	OffsetDateTime::parse(time.trim(), &Rfc3339).ok()
+}
+
+fn with_inclusive_prefixes(method: Method, transform: &Element) -> Method {
+	let Method::Exclusive { .. } = method else {
+		return method;
+	};
+
+	let inclusive_prefixes = transform
+		.child(EC, "InclusiveNamespaces")
+		.and_then(|x| x.attribute("PrefixList"))
+		.map(|x| x.split_whitespace().map(str::to_owned).collect())
+		.unwrap_or_default();
+
+	Method::Exclusive { inclusive_prefixes }
+}
+
+fn digest_algorithm(algorithm: &str) -> Option<&'static digest::Algorithm> {
+	match algorithm {
+		"http://www.w3.org/2000/09/xmldsig#sha1" => Some(&digest::SHA1_FOR_LEGACY_USE_ONLY),
+		"http://www.w3.org/2001/04/xmlenc#sha256" => Some(&digest::SHA256),
+		"http://www.w3.org/2001/04/xmldsig-more#sha384" => Some(&digest::SHA384),
+		"http://www.w3.org/2001/04/xmlenc#sha512" => Some(&digest::SHA512),
+		_ => None,
+	}
+}
+
+fn signature_algorithm(algorithm: &str) -> Option<&'static dyn signature::VerificationAlgorithm> {
+	match algorithm {
+		"http://www.w3.org/2000/09/xmldsig#rsa-sha1" => Some(&signature::RSA_PKCS1_2048_8192_SHA1_FOR_LEGACY_USE_ONLY),
+		"http://www.w3.org/2001/04/xmldsig-more#rsa-sha256" => Some(&signature::RSA_PKCS1_2048_8192_SHA256),
+		"http://www.w3.org/2001/04/xmldsig-more#rsa-sha384" => Some(&signature::RSA_PKCS1_2048_8192_SHA384),
+		"http://www.w3.org/2001/04/xmldsig-more#rsa-sha512" => Some(&signature::RSA_PKCS1_2048_8192_SHA512),
+		"http://www.w3.org/2001/04/xmldsig-more#ecdsa-sha256" => Some(&signature::ECDSA_P256_SHA256_FIXED),
+		"http://www.w3.org/2001/04/xmldsig-more#ecdsa-sha384" => Some(&signature::ECDSA_P384_SHA384_FIXED),
+		_ => None,
+	}
+}
+
+fn decode_base64(text: &str) -> Option<Vec<u8>> {
+	let text: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+	STANDARD.decode(text).ok()
+}
@@ -0,0 +1,161 @@
+# Potpisani računi za tests/xmldsig.rs
+#
+# python3 tests/data/generate.py (potrebni su paket cryptography i xmllint)
+# Certifikati vrijede od 2025. do 2045., a vrijeme potpisivanja je 2026-01-15T10:00:00Z.
+
+import base64, datetime, hashlib, os, subprocess, tempfile
+from cryptography import x509
+from cryptography.hazmat.primitives import hashes, serialization
+from cryptography.hazmat.primitives.asymmetric import padding, rsa
+from cryptography.x509.oid import NameOID
+
+DIR = os.path.dirname(os.path.abspath(__file__))
+DS = 'http://www.w3.org/2000/09/xmldsig#'
+XADES = 'http://uri.etsi.org/01903/v1.3.2#'
+EXC = 'http://www.w3.org/2001/10/xml-exc-c14n#'
+SHA256 = 'http://www.w3.org/2001/04/xmlenc#sha256'
+NOT_BEFORE = datetime.datetime(2025, 1, 1, tzinfo=datetime.timezone.utc)
+NOT_AFTER = datetime.datetime(2045, 1, 1, tzinfo=datetime.timezone.utc)
+
+
+def name(cn):
+	return x509.Name([
+		x509.NameAttribute(NameOID.COUNTRY_NAME, 'HR'),
+		x509.NameAttribute(NameOID.ORGANIZATION_NAME, 'Test RDC'),
+		x509.NameAttribute(NameOID.COMMON_NAME, cn),
+	])
+
+
+def certificate(subject, key, issuer, issuer_key, ca):
+	key_usage = dict(
+		digital_signature=not ca, content_commitment=not ca, key_encipherment=False, data_encipherment=False,
+		key_agreement=False, key_cert_sign=ca, crl_sign=ca, encipher_only=False, decipher_only=False,
+	)
+	return (
+		x509.CertificateBuilder()
+		.subject_name(subject)
+		.issuer_name(issuer)
+		.public_key(key.public_key())
+		.serial_number(x509.random_serial_number())
+		.not_valid_before(NOT_BEFORE)
+		.not_valid_after(NOT_AFTER)
+		.add_extension(x509.BasicConstraints(ca=ca, path_length=None), critical=True)
+		.add_extension(x509.KeyUsage(**key_usage), critical=True)
+		.sign(issuer_key, hashes.SHA256())
+	)
+
+
+def key():
+	return rsa.generate_private_key(public_exponent=65537, key_size=2048)
+
+
+def c14n(xml):
+	with tempfile.NamedTemporaryFile('w', suffix='.xml', delete=False) as f:
+		f.write(xml)
+	try:
+		return subprocess.check_output(['xmllint', '--exc-c14n', f.name])
+	finally:
+		os.unlink(f.name)
+
+
+def b64sha(data):
+	return base64.b64encode(hashlib.sha256(data).digest()).decode()
+
+
+def reference(uri, transforms, digest, type=''):
+	transforms = ''.join(f'<ds:Transform Algorithm="{x}"/>' for x in transforms)
+	type = f' Type="{type}"' if type else ''
+	return (
+		f'<ds:Reference{type} URI="{uri}"><ds:Transforms>{transforms}</ds:Transforms>'
+		f'<ds:DigestMethod Algorithm="{SHA256}"/><ds:DigestValue>{digest}</ds:DigestValue></ds:Reference>'
+	)
+
+
+def sign(invoice, signer, signer_key, signed_properties_reference=True, chain=()):
+	der = signer.public_bytes(serialization.Encoding.DER)
+	signed_properties = (
+		'<xades:SignedProperties Id="xades-props"><xades:SignedSignatureProperties>'
+		'<xades:SigningTime>2026-01-15T10:00:00Z</xades:SigningTime>'
+		'<xades:SigningCertificate><xades:Cert><xades:CertDigest>'
+		f'<ds:DigestMethod Algorithm="{SHA256}"/><ds:DigestValue>{b64sha(der)}</ds:DigestValue>'
+		'</xades:CertDigest><xades:IssuerSerial>'
+		f'<ds:X509IssuerName>{signer.issuer.rfc4514_string()}</ds:X509IssuerName>'
+		f'<ds:X509SerialNumber>{signer.serial_number}</ds:X509SerialNumber>'
+		'</xades:IssuerSerial></xades:Cert></xades:SigningCertificate>'
+		'</xades:SignedSignatureProperties></xades:SignedProperties>'
+	)
+	namespaces = f'xmlns:xades="{XADES}" xmlns:ds="{DS}" '
+	signed_properties_c14n = c14n(signed_properties.replace('<xades:SignedProperties ', f'<xades:SignedProperties {namespaces}', 1))
+
+	mark = '<!--SIGNATURE-->'
+	extension = f'''
+		<ext:UBLExtension>
+			<ext:ExtensionContent>
+				<sig:UBLDocumentSignatures xmlns:sig="urn:oasis:names:specification:ubl:schema:xsd:CommonSignatureComponents-2" xmlns:sac="urn:oasis:names:specification:ubl:schema:xsd:SignatureAggregateComponents-2" xmlns:sbc="urn:oasis:names:specification:ubl:schema:xsd:SignatureBasicComponents-2">
+					<sac:SignatureInformation>
+						<cbc:ID>urn:oasis:names:specification:ubl:signature:1</cbc:ID>
+						<sbc:ReferencedSignatureID>urn:oasis:names:specification:ubl:signature:Invoice</sbc:ReferencedSignatureID>{mark}
+					</sac:SignatureInformation>
+				</sig:UBLDocumentSignatures>
+			</ext:ExtensionContent>
+		</ext:UBLExtension>
+	</ext:UBLExtensions>'''
+	document = invoice.replace('\n\t</ext:UBLExtensions>', extension, 1)
+	document_digest = b64sha(c14n(document.replace(mark, '\n\t\t\t\t\t\t')))
+
+	references = reference('', [DS + 'enveloped-signature', EXC], document_digest)
+	if signed_properties_reference:
+		references += reference('#xades-props', [EXC], b64sha(signed_properties_c14n), 'http://uri.etsi.org/01903#SignedProperties')
+	signed_info = (
+		f'<ds:SignedInfo><ds:CanonicalizationMethod Algorithm="{EXC}"/>'
+		'<ds:SignatureMethod Algorithm="http://www.w3.org/2001/04/xmldsig-more#rsa-sha256"/>'
+		f'{references}</ds:SignedInfo>'
+	)
+	signed_info_c14n = c14n(signed_info.replace('<ds:SignedInfo>', f'<ds:SignedInfo xmlns:ds="{DS}">', 1))
+	value = base64.b64encode(signer_key.sign(signed_info_c14n, padding.PKCS1v15(), hashes.SHA256())).decode()
+
+	certificates = ''.join(
+		f'<ds:X509Certificate>{base64.b64encode(x.public_bytes(serialization.Encoding.DER)).decode()}</ds:X509Certificate>'
+		for x in [signer, *chain]
+	)
+	signature = (
+		f'\n\t\t\t\t\t\t<ds:Signature xmlns:ds="{DS}" Id="sig-1">{signed_info}'
+		f'<ds:SignatureValue>{value}</ds:SignatureValue>'
+		f'<ds:KeyInfo><ds:X509Data>{certificates}</ds:X509Data></ds:KeyInfo>'
+		f'<ds:Object><xades:QualifyingProperties xmlns:xades="{XADES}" Target="#sig-1">{signed_properties}</xades:QualifyingProperties></ds:Object>'
+		'</ds:Signature>'
+	)
+	return document.replace(mark, signature)
+
+
+def pem(path, cert):
+	with open(os.path.join(DIR, path), 'wb') as f:
+		f.write(cert.public_bytes(serialization.Encoding.PEM))
+
+
+def write(path, text):
+	with open(os.path.join(DIR, path), 'w') as f:
+		f.write(text)
+
+
+ca_key, other_key, signer_key = key(), key(), key()
+ca = certificate(name('Test Root CA'), ca_key, name('Test Root CA'), ca_key, True)
+other = certificate(name('Other Root CA'), other_key, name('Other Root CA'), other_key, True)
+signer = certificate(name('Ivana Horvat'), signer_key, ca.subject, ca_key, False)
+pem('trust/ca.pem', ca)
+pem('other-trust/other.pem', other)
+
+invoice = open(os.path.join(DIR, 'invoice.xml')).read()
+write('signed.xml', sign(invoice, signer, signer_key))
+write('signed-without-properties.xml', sign(invoice, signer, signer_key, signed_properties_reference=False))
+
+# Instrukcije za obradu prije korijenskog elementa i unutar potpisanog sadržaja
+with_pi = invoice.replace('?>\n', '?>\n<?xml-stylesheet type="text/xsl" href="eracun.xsl"?>\n', 1).replace(
+	'\t<cbc:Note>', '\t<?erp-export  batch="42" ?>\n\t<cbc:Note>', 1
+)
+write('signed-with-pi.xml', sign(with_pi, signer, signer_key))
+
+# Potpisnikov certifikat izdao je certifikat koji nije CA
+issued_key = key()
+issued = certificate(name('Marko Marić'), issued_key, signer.subject, signer_key, False)
+write('signed-by-end-entity.xml', sign(invoice, issued, issued_key, chain=[signer]))
@@ -0,0 +1,136 @@
+// Provjera potpisa na računima iz tests/data, koje generira tests/data/generate.py
+
+use eracun_xml_to_pdf::{
+	signature::SignatureInfo,
+	xmldsig::{self, TrustStore, Verification},
+};
+use std::path::Path;
+
+const SIGNED: &str = include_str!("data/signed.xml");
+const WITHOUT_PROPERTIES: &str = include_str!("data/signed-without-properties.xml");
+const OTHER_CA: &str = include_str!("data/other-trust/other.pem");
+
+fn trust_store(dir: &str) -> TrustStore {
+	TrustStore::load(&Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/data").join(dir)).unwrap()
+}
+
+// Potpis provjeravamo kao glavni program: podatke o potpisniku daje parser
+fn verify(document: &str, trust: &str) -> Verification {
+	let signature = eracun_xml_to_pdf::from_str(document)
+		.unwrap()
+		.signature
+		.unwrap_or_default();
+	xmldsig::verify(document.as_bytes(), &trust_store(trust), &signature)
+}
+
+fn assert_invalid(verification: Verification, reason: &str) {
+	match verification {
+		Verification::Invalid(reasons) => {
+			assert!(reasons.iter().any(|x| x.contains(reason)), "{:?} ne sadrži '{}'", reasons, reason)
+		},
+		x => panic!("očekivan neispravan potpis, a ne {:?}", x),
+	}
+}
+
+fn other_ca_base64() -> String {
+	OTHER_CA.lines().filter(|x| !x.starts_with("-----")).collect()
+}
+
+#[test]
+fn valid_signature() {
+	assert_eq!(verify(SIGNED, "trust"), Verification::Valid);
+}
+
+// Instrukcije za obradu su dio potpisanog sadržaja
+#[test]
+fn processing_instructions() {
+	let document = include_str!("data/signed-with-pi.xml");
+	assert_eq!(verify(document, "trust"), Verification::Valid);
+
+	for (from, to) in [
+		(r#"<?erp-export  batch="42" ?>"#, r#"<?erp-export  batch="43" ?>"#),
+		(r#"<?erp-export  batch="42" ?>"#, ""),
+		(r#"href="eracun.xsl"?>"#, r#"href="drugi.xsl"?>"#),
+	] {
+		let tampered = document.replace(from, to);
+		assert_ne!(tampered, document);
+		assert_invalid(verify(&tampered, "trust"), "sažetak reference '' ne odgovara sadržaju");
+	}
+}
+
+#[test]
+fn tampered_document() {
+	let tampered = SIGNED.replace(">1234.56</cbc:PayableAmount>", ">1.00</cbc:PayableAmount>");
+	assert_ne!(tampered, SIGNED);
+	assert_invalid(verify(&tampered, "trust"), "sažetak reference '' ne odgovara sadržaju");
+}
+
+#[test]
+fn tampered_signed_properties() {
+	let tampered = SIGNED.replace("2026-01-15T10:00:00Z", "2026-01-15T11:00:00Z");
+	assert_invalid(verify(&tampered, "trust"), "sažetak reference '#xades-props' ne odgovara sadržaju");
+}
+
+#[test]
+fn untrusted_chain() {
+	match verify(SIGNED, "other-trust") {
+		Verification::UntrustedChain(reasons) => assert!(reasons[0].contains("nije pronađen pouzdani izdavatelj")),
+		x => panic!("očekivan nepouzdan lanac, a ne {:?}", x),
+	}
+}
+
+#[test]
+fn wrong_signer_certificate() {
+	// Potpis ostaje isti, ali ds:KeyInfo navodi drugi certifikat
+	let start = SIGNED.find("<ds:X509Certificate>").unwrap() + "<ds:X509Certificate>".len();
+	let end = SIGNED.find("</ds:X509Certificate>").unwrap();
+	let forged = std::format!("{}{}{}", &SIGNED[..start], other_ca_base64(), &SIGNED[end..]);
+
+	let verification = verify(&forged, "other-trust");
+	assert_invalid(verification.clone(), "vrijednost potpisa nije ispravna");
+	assert_invalid(verification, "xades:SigningCertificate ne odgovara certifikatu potpisnika");
+}
+
+#[test]
+fn signer_differs_from_signature_info() {
+	let mut signature = SignatureInfo::default();
+	signature.set_certificate(&other_ca_base64());
+	let verification = xmldsig::verify(SIGNED.as_bytes(), &trust_store("trust"), &signature);
+	assert_invalid(verification, "certifikat potpisnika ne odgovara prikazanom potpisu");
+}
+
+#[test]
+fn signature_outside_ubl_extensions() {
+	// Potpis premješten iz sac:SignatureInformation ne smije se prihvatiti
+	let moved = SIGNED
+		.replace("<sac:SignatureInformation>", "<sac:Other>")
+		.replace("</sac:SignatureInformation>", "</sac:Other>");
+	assert_invalid(verify(&moved, "trust"), "dokument nije potpisan");
+}
+
+#[test]
+fn duplicate_id() {
+	let duplicated = SIGNED.replace("<cbc:Note>", "<cbc:Note Id=\"xades-props\">");
+	assert_ne!(duplicated, SIGNED);
+	assert_invalid(verify(&duplicated, "trust"), "ID 'xades-props' se u dokumentu pojavljuje više puta");
+}
+
+#[test]
+fn missing_signed_properties_reference() {
+	assert_invalid(verify(WITHOUT_PROPERTIES, "trust"), "potpis ne obuhvaća xades:SignedProperties");
+}
+
+#[test]
+fn doctype_rejected() {
+	let document = SIGNED.replacen("?>", "?><!DOCTYPE Invoice []>", 1);
+	let verification = xmldsig::verify(document.as_bytes(), &trust_store("trust"), &SignatureInfo::default());
+	assert_invalid(verification, "DTD nije dopušten");
+}
+
+#[test]
+fn issuer_is_not_ca() {
+	match verify(include_str!("data/signed-by-end-entity.xml"), "trust") {
+		Verification::UntrustedChain(reasons) => assert!(reasons[0].contains("ne smije izdavati certifikate")),
+		x => panic!("očekivan nepouzdan lanac, a ne {:?}", x),
+	}
+}